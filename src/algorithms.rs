@@ -0,0 +1,379 @@
+//! Algoritmos clássicos sobre grafos, implementados sobre o `trait` [`Graph`].
+//!
+//! As funções são genéricas em `G: Graph + ?Sized`: aceitam tanto `&dyn Graph` quanto um tipo
+//! concreto, caso em que as chamadas aos métodos do grafo são resolvidas estaticamente.
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use bitvec::bitvec;
+
+use crate::{Edge, Graph, GraphMat, GraphMut, Node, Weight};
+
+/// Conjuntos disjuntos (union-find) com compressão de caminho e união por tamanho.
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSet {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+    /// Retorna o representante do conjunto de `x`.
+    pub fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            // compressão de caminho pela metade
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+    /// Une os conjuntos de `a` e `b`, retornando `false` caso já fossem o mesmo.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+/// Falha se `g` é dirigido, caso em que árvores geradoras e componentes conexas não estão
+/// definidas.
+fn undirected<G: Graph + ?Sized>(g: &G, algorithm: &str) -> Result<(), String> {
+    if g.is_directed() {
+        return Err(format!("{algorithm} não se aplica a grafos dirigidos"));
+    }
+    Ok(())
+}
+
+/// Árvore (ou floresta, se o grafo for desconexo) geradora mínima pelo algoritmo de Prim, em
+/// O(m log n). Falha em grafos dirigidos.
+pub fn prim<G: Graph + ?Sized>(g: &G) -> Result<Vec<Edge>, String> {
+    undirected(g, "Prim")?;
+    let n = g.node_count();
+    let mut in_tree = bitvec![0; n];
+    let mut tree = Vec::with_capacity(n.saturating_sub(1));
+    let mut heap = BinaryHeap::new();
+
+    for root in g.nodes() {
+        if in_tree[root as usize] {
+            continue;
+        }
+        // A raiz de cada componente entra sem aresta associada.
+        heap.push(Reverse((Weight::from(0.0), root, None)));
+        while let Some(Reverse((w, v, parent))) = heap.pop() {
+            if in_tree[v as usize] {
+                continue;
+            }
+            in_tree.set(v as usize, true);
+            if let Some(parent) = parent {
+                tree.push(Edge(parent, v, w));
+            }
+            for Edge(_, u, w) in g.incident_edges(v) {
+                if !in_tree[u as usize] {
+                    heap.push(Reverse((w, u, Some(v))));
+                }
+            }
+        }
+    }
+    Ok(tree)
+}
+
+/// Árvore (ou floresta) geradora mínima pelo algoritmo de Kruskal, em O(m log m). Falha em
+/// grafos dirigidos.
+pub fn kruskal<G: Graph + ?Sized>(g: &G) -> Result<Vec<Edge>, String> {
+    undirected(g, "Kruskal")?;
+    let mut edges = g.unique_edges().collect::<Vec<_>>();
+    edges.sort_unstable_by_key(|e| e.2);
+
+    let mut sets = DisjointSet::new(g.node_count());
+    Ok(edges
+        .into_iter()
+        .filter(|Edge(a, b, _)| sets.union(*a as usize, *b as usize))
+        .collect())
+}
+
+/// Resultado de uma busca de caminhos mínimos a partir de uma única origem.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths {
+    pub source: Node,
+    /// Distância da origem até cada nó, ou `None` se o nó for inalcançável.
+    pub distances: Vec<Option<Weight>>,
+    /// Nó anterior a cada nó no caminho mínimo a partir da origem.
+    pub predecessors: Vec<Option<Node>>,
+}
+
+impl ShortestPaths {
+    /// Reconstrói o caminho da origem até `target`, incluindo ambos.
+    pub fn path_to(&self, target: Node) -> Option<Vec<Node>> {
+        self.distances[target as usize]?;
+        let mut path = vec![target];
+        let mut current = target;
+        while let Some(previous) = self.predecessors[current as usize] {
+            path.push(previous);
+            current = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Caminhos mínimos a partir de `source` pelo algoritmo de Dijkstra. Pressupõe pesos não
+/// negativos.
+pub fn dijkstra<G: Graph + ?Sized>(g: &G, source: Node) -> ShortestPaths {
+    let n = g.node_count();
+    let mut distances = vec![None; n];
+    let mut predecessors = vec![None; n];
+    let mut done = bitvec![0; n];
+
+    let mut heap = BinaryHeap::new();
+    distances[source as usize] = Some(Weight::from(0.0));
+    heap.push(Reverse((Weight::from(0.0), source)));
+    while let Some(Reverse((d, v))) = heap.pop() {
+        if done[v as usize] {
+            continue;
+        }
+        done.set(v as usize, true);
+        for Edge(_, u, w) in g.incident_edges(v) {
+            let candidate = d + w;
+            if distances[u as usize].is_none_or(|old| candidate < old) {
+                distances[u as usize] = Some(candidate);
+                predecessors[u as usize] = Some(v);
+                heap.push(Reverse((candidate, u)));
+            }
+        }
+    }
+    ShortestPaths {
+        source,
+        distances,
+        predecessors,
+    }
+}
+
+/// Distâncias mínimas entre todos os pares de nós pelo algoritmo de Floyd–Warshall, em O(n³).
+///
+/// `result[a][b]` é `None` quando `b` não é alcançável a partir de `a`.
+pub fn floyd_warshall<G: Graph + ?Sized>(g: &G) -> Vec<Vec<Option<Weight>>> {
+    let n = g.node_count();
+    let mut dist = vec![vec![None; n]; n];
+    for a in g.nodes() {
+        dist[a as usize][a as usize] = Some(Weight::from(0.0));
+        for Edge(_, b, w) in g.incident_edges(a) {
+            let old = &mut dist[a as usize][b as usize];
+            if old.is_none_or(|old| w < old) {
+                *old = Some(w);
+            }
+        }
+    }
+    for k in 0..n {
+        let through = dist[k].clone();
+        for row in dist.iter_mut() {
+            let Some(ik) = row[k] else {
+                continue;
+            };
+            for (ij, kj) in row.iter_mut().zip(&through) {
+                let Some(kj) = *kj else {
+                    continue;
+                };
+                if ij.is_none_or(|ij| ik + kj < ij) {
+                    *ij = Some(ik + kj);
+                }
+            }
+        }
+    }
+    dist
+}
+
+/// Fecho métrico de `g`: um grafo completo em que o peso de cada aresta é a distância mínima
+/// entre seus nós em `g`.
+///
+/// Útil para aplicar heurísticas de TSP a grafos esparsos lidos por [`crate::fill_graph`].
+pub fn metric_closure<G: Graph + ?Sized>(g: &G) -> GraphMat {
+    let mut closure = GraphMat::default();
+    closure.add_nodes(g.node_count());
+    for (a, row) in floyd_warshall(g).into_iter().enumerate() {
+        for (b, w) in row.into_iter().enumerate() {
+            if let Some(w) = w.filter(|_| a != b) {
+                closure.add_edge(a as Node, b as Node, w);
+            }
+        }
+    }
+    closure
+}
+
+/// Componentes conexas do grafo, cada uma como lista de seus nós. Falha em grafos dirigidos.
+pub fn connected_components<G: Graph + ?Sized>(g: &G) -> Result<Vec<Vec<Node>>, String> {
+    undirected(g, "Componentes conexas")?;
+    let mut visited = bitvec![0; g.node_count()];
+    let mut components = Vec::new();
+    for root in g.nodes() {
+        if visited[root as usize] {
+            continue;
+        }
+        visited.set(root as usize, true);
+        // busca em profundidade iterativa
+        let mut component = Vec::new();
+        let mut stack = vec![root];
+        while let Some(v) = stack.pop() {
+            component.push(v);
+            for u in g.neighbours(v) {
+                if !visited[u as usize] {
+                    visited.set(u as usize, true);
+                    stack.push(u);
+                }
+            }
+        }
+        components.push(component);
+    }
+    Ok(components)
+}
+
+/// Estatísticas sobre o grau dos nós de um grafo.
+#[derive(Debug, Clone, Default)]
+pub struct DegreeStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    /// `histogram[d]` é a quantidade de nós com grau `d`.
+    pub histogram: Vec<usize>,
+}
+
+/// Estatísticas do grau dos nós, sem contar laços. Em grafos dirigidos, é o grau de saída.
+pub fn degree_stats<G: Graph + ?Sized>(g: &G) -> DegreeStats {
+    let degrees = g
+        .nodes()
+        .map(|v| g.neighbours(v).filter(|u| *u != v).count())
+        .collect::<Vec<_>>();
+    let Some(&max) = degrees.iter().max() else {
+        return DegreeStats::default();
+    };
+    let mut histogram = vec![0; max + 1];
+    for &d in &degrees {
+        histogram[d] += 1;
+    }
+    DegreeStats {
+        min: *degrees.iter().min().unwrap(),
+        max,
+        mean: degrees.iter().sum::<usize>() as f64 / degrees.len() as f64,
+        histogram,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::GraphList;
+
+    /// Grafo aleatório não dirigido com `n` nós, pesos inteiros e cada aresta presente com
+    /// probabilidade `density`.
+    fn random_graph(n: usize, density: f64, rand: &mut impl Rng) -> GraphMat {
+        let mut g = GraphMat::default();
+        g.add_nodes(n);
+        for a in 0..n as Node {
+            for b in a + 1..n as Node {
+                if rand.gen_bool(density) {
+                    g.add_edge(a, b, (rand.gen_range(1..50) as f64).into());
+                }
+            }
+        }
+        g
+    }
+
+    fn total(edges: &[Edge]) -> Weight {
+        edges.iter().map(|e| e.2).sum()
+    }
+
+    #[test]
+    fn prim_and_kruskal_agree() {
+        let mut rand = StdRng::seed_from_u64(37);
+        for _ in 0..100 {
+            let n = rand.gen_range(1..12);
+            let g = random_graph(n, 0.4, &mut rand);
+            let (p, k) = (prim(&g).unwrap(), kruskal(&g).unwrap());
+            assert_eq!(total(&p), total(&k));
+            // uma floresta tem n - c arestas
+            let components = connected_components(&g).unwrap().len();
+            assert_eq!(p.len(), n - components);
+            assert_eq!(k.len(), n - components);
+        }
+    }
+
+    #[test]
+    fn dijkstra_matches_floyd_warshall() {
+        let mut rand = StdRng::seed_from_u64(41);
+        for _ in 0..50 {
+            let n = rand.gen_range(1..10);
+            let g = random_graph(n, 0.3, &mut rand);
+            let all = floyd_warshall(&g);
+            for source in g.nodes() {
+                let paths = dijkstra(&g, source);
+                assert_eq!(paths.distances, all[source as usize]);
+                // cada caminho reconstruído tem o comprimento da distância
+                for target in g.nodes() {
+                    let Some(path) = paths.path_to(target) else {
+                        continue;
+                    };
+                    let length: Weight = path
+                        .windows(2)
+                        .map(|e| g.get_edge_weight(e[0], e[1]).unwrap())
+                        .sum();
+                    assert_eq!(Some(length), paths.distances[target as usize]);
+                }
+            }
+            let closure = metric_closure(&g);
+            for (a, b) in g.nodes().flat_map(|a| g.nodes().map(move |b| (a, b))) {
+                let expected = all[a as usize][b as usize].filter(|_| a != b);
+                assert_eq!(closure.get_edge_weight(a, b), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn counts_components() {
+        let mut g = GraphList::default();
+        g.add_nodes(6);
+        g.add_edge(0, 1, 1.0.into());
+        g.add_edge(1, 2, 1.0.into());
+        g.add_edge(3, 4, 1.0.into());
+        let mut components = connected_components(&g).unwrap();
+        for c in &mut components {
+            c.sort();
+        }
+        components.sort();
+        assert_eq!(components, [vec![0, 1, 2], vec![3, 4], vec![5]]);
+    }
+
+    #[test]
+    fn degree_stats_ignores_loops() {
+        let mut g = GraphList::default();
+        g.add_nodes(3);
+        g.add_edge(0, 0, 1.0.into());
+        g.add_edge(0, 1, 1.0.into());
+        g.add_edge(1, 2, 1.0.into());
+        let stats = degree_stats(&g);
+        assert_eq!((stats.min, stats.max), (1, 2));
+        assert_eq!(stats.histogram, [0, 2, 1]);
+        assert!((stats.mean - 4.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rejects_directed_graphs() {
+        let mut g = GraphMat::directed();
+        g.add_nodes(2);
+        g.add_edge(0, 1, 1.0.into());
+        assert!(prim(&g).is_err());
+        assert!(kruskal(&g).is_err());
+        assert!(connected_components(&g).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+//! Pesos adaptativos de operadores para a busca adaptativa em vizinhança grande (ALNS): os
+//! operadores são sorteados por roleta, pontuados pelo resultado de cada uso e, ao fim de cada
+//! segmento de iterações, seus pesos se aproximam da pontuação média obtida.
+use rand::Rng;
+
+/// Resultado de uma iteração, do mais ao menos recompensado.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// A solução obtida é a melhor encontrada até agora.
+    NewBest,
+    /// A solução obtida é melhor que a corrente.
+    Improved,
+    /// A solução obtida não é melhor, mas foi aceita.
+    Accepted,
+    Rejected,
+}
+
+/// Estatísticas de uso de um operador.
+#[derive(Debug, Clone, Default)]
+pub struct OperatorStats {
+    pub name: &'static str,
+    pub uses: usize,
+    pub new_best: usize,
+    pub improved: usize,
+    pub accepted: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdaptiveWeights {
+    weights: Vec<f64>,
+    /// Pontuação acumulada e usos de cada operador no segmento atual.
+    scores: Vec<f64>,
+    segment_uses: Vec<usize>,
+    stats: Vec<OperatorStats>,
+    /// Quanto os pesos se movem em direção à pontuação média a cada segmento, entre 0 e 1.
+    pub reaction: f64,
+    /// Recompensas por [`Outcome::NewBest`], [`Outcome::Improved`] e [`Outcome::Accepted`].
+    pub rewards: [f64; 3],
+}
+
+impl AdaptiveWeights {
+    pub fn new(names: &[&'static str]) -> Self {
+        let n = names.len();
+        Self {
+            weights: vec![1.0; n],
+            scores: vec![0.0; n],
+            segment_uses: vec![0; n],
+            stats: names
+                .iter()
+                .map(|&name| OperatorStats {
+                    name,
+                    ..Default::default()
+                })
+                .collect(),
+            reaction: 0.2,
+            rewards: [33.0, 13.0, 9.0],
+        }
+    }
+
+    /// Volta todos os pesos a 1, mantendo as estatísticas, para uma nova execução.
+    pub fn reset(&mut self) {
+        self.weights.fill(1.0);
+        self.scores.fill(0.0);
+        self.segment_uses.fill(0);
+    }
+
+    /// Sorteia um operador com probabilidade proporcional a seu peso.
+    pub fn select(&mut self, rand: &mut impl Rng) -> usize {
+        let total: f64 = self.weights.iter().sum();
+        let mut roulette = rand.gen::<f64>() * total;
+        let chosen = self
+            .weights
+            .iter()
+            .position(|w| {
+                roulette -= w;
+                roulette < 0.0
+            })
+            // erros de arredondamento podem deixar a roleta passar do último
+            .unwrap_or(self.weights.len() - 1);
+        self.segment_uses[chosen] += 1;
+        self.stats[chosen].uses += 1;
+        chosen
+    }
+
+    /// Pontua o operador pelo resultado da iteração em que foi usado.
+    pub fn reward(&mut self, operator: usize, outcome: Outcome) {
+        let stats = &mut self.stats[operator];
+        let reward = match outcome {
+            Outcome::NewBest => {
+                stats.new_best += 1;
+                self.rewards[0]
+            }
+            Outcome::Improved => {
+                stats.improved += 1;
+                self.rewards[1]
+            }
+            Outcome::Accepted => {
+                stats.accepted += 1;
+                self.rewards[2]
+            }
+            Outcome::Rejected => 0.0,
+        };
+        self.scores[operator] += reward;
+    }
+
+    /// Termina um segmento: o peso de cada operador usado se aproxima de sua pontuação média
+    /// no segmento.
+    pub fn end_segment(&mut self) {
+        for ((weight, score), uses) in self
+            .weights
+            .iter_mut()
+            .zip(&mut self.scores)
+            .zip(&mut self.segment_uses)
+        {
+            if *uses > 0 {
+                *weight = (1.0 - self.reaction) * *weight + self.reaction * *score / *uses as f64;
+                // um operador sem sucesso ainda deve poder ser sorteado
+                *weight = weight.max(0.01);
+            }
+            *score = 0.0;
+            *uses = 0;
+        }
+    }
+
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    pub fn stats(&self) -> &[OperatorStats] {
+        &self.stats
+    }
+
+    /// Imprime uma linha por operador no formato `operator;uses;new_best;improved;accepted;weight`.
+    pub fn print_stats(&self) {
+        println!("operator;uses;new_best;improved;accepted;weight");
+        for (stats, weight) in self.stats.iter().zip(&self.weights) {
+            println!(
+                "{};{};{};{};{};{}",
+                stats.name, stats.uses, stats.new_best, stats.improved, stats.accepted, weight
+            );
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Esquemas de resfriamento do recozimento simulado (SA), compartilhados entre os problemas. O
+//! [`Annealing`] guarda a temperatura e decide a aceitação dos movimentos; cabe ao chamador
+//! sortear os vizinhos e avisar quando encontra uma nova melhor solução.
+use rand::Rng;
+
+/// Como a temperatura cai ao fim de cada estágio. O significado de [`Schedule::alpha`] depende
+/// do esquema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cooling {
+    /// `T ← T - alpha`.
+    Linear,
+    /// `T ← alpha T`.
+    Geometric,
+    /// `T_k = T_0 / ln(k + e)`, em que `k` é o número do estágio. Ignora `alpha`. Cai devagar
+    /// demais para chegar a `epsilon` na prática, então deve ser usado com
+    /// [`Schedule::stages_max`].
+    Logarithmic,
+    /// Lundy e Mees: `T ← T / (1 + alpha T)`.
+    LundyMees,
+    /// Huang, Romeo e Sangiovanni-Vincentelli: `T ← T max(exp(-alpha T / σ), 0.9)`, em que `σ` é
+    /// o desvio padrão do custo da solução corrente ao longo do estágio. Resfria devagar enquanto
+    /// a busca ainda oscila muito em relação à temperatura; quando está quase congelada (`σ`
+    /// pequeno), a fórmula original zeraria a temperatura, então cada estágio reduz no máximo
+    /// 10%, como um resfriamento geométrico.
+    Adaptive,
+}
+
+impl std::str::FromStr for Cooling {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(Self::Linear),
+            "geometric" => Ok(Self::Geometric),
+            "log" => Ok(Self::Logarithmic),
+            "lundy" => Ok(Self::LundyMees),
+            "adaptive" => Ok(Self::Adaptive),
+            _ => Err(format!("Resfriamento desconhecido: {s}")),
+        }
+    }
+}
+
+/// Menor fator aplicado à temperatura em um estágio do [`Cooling::Adaptive`].
+const ADAPTIVE_MIN_FACTOR: f64 = 0.9;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Schedule {
+    pub cooling: Cooling,
+    /// Decremento (linear), fator (geométrico), `β` (Lundy–Mees) ou `λ` (adaptativo).
+    pub alpha: f64,
+    /// Temperatura em que a busca termina.
+    pub epsilon: f64,
+    /// Máximo de iterações por temperatura.
+    pub i_max: usize,
+    /// Se diferente de 0, o estágio termina antes de `i_max` iterações ao aceitar essa quantidade
+    /// de movimentos, como no critério de equilíbrio de Kirkpatrick: em temperaturas altas, quase
+    /// tudo é aceito e o equilíbrio é atingido rápido.
+    pub accepted_max: usize,
+    /// Se diferente de 0, limita a quantidade de estágios.
+    pub stages_max: usize,
+    /// Se diferente de 0, reaquece após essa quantidade de estágios sem nova melhor solução.
+    pub reheat_after: usize,
+    /// Fator de reaquecimento, limitado pela temperatura inicial.
+    pub reheat_factor: f64,
+    pub reheats_max: usize,
+}
+
+/// Temperatura em que uma piora média dentre as amostradas é aceita com probabilidade
+/// `acceptance`, isto é, `T_0 = -média / ln(acceptance)`. Amostras que não são pioras são
+/// ignoradas; devolve `None` se não houver nenhuma. Falha se `acceptance` não estiver em
+/// `(0, 1)`, onde a temperatura não seria positiva e finita.
+pub fn initial_temperature(
+    worsenings: impl IntoIterator<Item = f64>,
+    acceptance: f64,
+) -> Result<Option<f64>, String> {
+    if !(acceptance > 0.0 && acceptance < 1.0) {
+        return Err(format!(
+            "A aceitação inicial deve estar em (0, 1), mas é {acceptance}"
+        ));
+    }
+    let (sum, count) = worsenings
+        .into_iter()
+        .filter(|w| *w > 0.0)
+        .fold((0.0, 0), |(sum, count), w| (sum + w, count + 1));
+    Ok((count > 0).then(|| -(sum / count as f64) / acceptance.ln()))
+}
+
+#[derive(Debug, Clone)]
+pub struct Annealing {
+    schedule: Schedule,
+    temp0: f64,
+    temp: f64,
+    stage: usize,
+    /// Iterações e movimentos aceitos no estágio atual.
+    iterations: usize,
+    accepted: usize,
+    /// Custo da solução corrente relativo ao início do estágio e a soma e soma dos quadrados
+    /// desse custo a cada iteração, para o resfriamento adaptativo.
+    cost: f64,
+    sum: f64,
+    sum_sq: f64,
+    improved: bool,
+    idle_stages: usize,
+    reheats: usize,
+}
+
+impl Annealing {
+    pub fn new(schedule: Schedule, temp0: f64) -> Self {
+        Self {
+            schedule,
+            temp0,
+            temp: temp0,
+            stage: 0,
+            iterations: 0,
+            accepted: 0,
+            cost: 0.0,
+            sum: 0.0,
+            sum_sq: 0.0,
+            improved: false,
+            idle_stages: 0,
+            reheats: 0,
+        }
+    }
+
+    pub fn temperature(&self) -> f64 {
+        self.temp
+    }
+
+    /// Decide se um movimento que piora a solução em `worsening` é aceito. Melhoras (valores
+    /// negativos) são sempre aceitas; pioras com probabilidade `exp(-worsening / T)`.
+    pub fn accept(&mut self, worsening: f64, rand: &mut impl Rng) -> bool {
+        self.iterations += 1;
+        let accepted = worsening <= 0.0 || rand.gen::<f64>() < (-worsening / self.temp).exp();
+        if accepted {
+            self.accepted += 1;
+            self.cost += worsening;
+        }
+        self.sum += self.cost;
+        self.sum_sq += self.cost * self.cost;
+        accepted
+    }
+
+    /// Registra que o estágio atual encontrou uma nova melhor solução.
+    pub fn improved(&mut self) {
+        self.improved = true;
+    }
+
+    /// Se o estágio atual atingiu o equilíbrio e a temperatura deve cair.
+    pub fn stage_done(&self) -> bool {
+        self.iterations >= self.schedule.i_max
+            || (self.schedule.accepted_max != 0 && self.accepted >= self.schedule.accepted_max)
+    }
+
+    /// Se a busca terminou.
+    pub fn is_frozen(&self) -> bool {
+        self.temp <= self.schedule.epsilon
+            || (self.schedule.stages_max != 0 && self.stage >= self.schedule.stages_max)
+    }
+
+    /// Encerra o estágio atual, resfriando ou, se estiver estagnado, reaquecendo.
+    pub fn next_stage(&mut self) {
+        let Schedule {
+            cooling,
+            alpha,
+            reheat_after,
+            reheat_factor,
+            reheats_max,
+            ..
+        } = self.schedule;
+
+        self.stage += 1;
+        self.temp = match cooling {
+            Cooling::Linear => self.temp - alpha,
+            Cooling::Geometric => self.temp * alpha,
+            // equivale a T_0 / ln(k + e), mas continua valendo depois de um reaquecimento
+            Cooling::Logarithmic => {
+                let k = self.stage as f64;
+                self.temp * (k - 1.0 + std::f64::consts::E).ln() / (k + std::f64::consts::E).ln()
+            }
+            Cooling::LundyMees => self.temp / (1.0 + alpha * self.temp),
+            Cooling::Adaptive => {
+                let n = self.iterations.max(1) as f64;
+                let mean = self.sum / n;
+                let sigma = (self.sum_sq / n - mean * mean).max(0.0).sqrt();
+                // com a busca parada, a fórmula zeraria a temperatura
+                self.temp * (-alpha * self.temp / sigma).exp().max(ADAPTIVE_MIN_FACTOR)
+            }
+        };
+
+        if self.improved {
+            self.idle_stages = 0;
+        } else {
+            self.idle_stages += 1;
+        }
+        if reheat_after != 0 && self.idle_stages >= reheat_after && self.reheats < reheats_max {
+            self.temp = (self.temp * reheat_factor).min(self.temp0);
+            self.idle_stages = 0;
+            self.reheats += 1;
+        }
+
+        self.iterations = 0;
+        self.accepted = 0;
+        self.cost = 0.0;
+        self.sum = 0.0;
+        self.sum_sq = 0.0;
+        self.improved = false;
+    }
+}
@@ -0,0 +1,162 @@
+use std::f64::consts::E;
+use std::time::{Duration, Instant};
+
+use exs::alns::{AdaptiveWeights, Outcome};
+use exs::knapsack::multidim::{self, read_mknap};
+use exs::knapsack::quadratic::{self, read_qkp};
+use exs::knapsack::{construction, lns, repair};
+use exs::knapsack::{read_knapsack, KnapsackSolution, Solution, UWeight, WithPenalty};
+use exs::{debug_to_kw, flag_value, has_flag, open_file};
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PParams {
+    pub i_max: usize,
+    /// Iterações entre atualizações dos pesos dos operadores.
+    pub segment: usize,
+    /// Fração mínima e máxima dos ítens escolhidos removidos a cada iteração.
+    pub q_min: f64,
+    pub q_max: f64,
+    pub temp0: f64,
+    pub alpha: f64,
+    pub penalty: UWeight,
+}
+
+const DESTROY: [&str; 2] = ["random_drop", "worst_drop"];
+const REPAIR: [&str; 2] = ["ratio_fill", "gain_fill"];
+
+fn run<S: KnapsackSolution>(
+    s0: &S,
+    pparams: PParams,
+    destroy: &mut AdaptiveWeights,
+    repair: &mut AdaptiveWeights,
+) -> (Duration, UWeight) {
+    let PParams {
+        i_max,
+        segment,
+        q_min,
+        q_max,
+        temp0: mut temp,
+        alpha,
+        ..
+    } = pparams;
+
+    let mut rand = rand::thread_rng();
+    destroy.reset();
+    repair.reset();
+
+    let now = Instant::now();
+    // toda solução passa pela reparação, então é sempre viável
+    let mut s = repair::decode(s0);
+    let mut s_best = s.clone();
+    for i in 1..=i_max {
+        let chosen = s.items().count_ones() as f64;
+        let q = rand.gen_range((q_min * chosen) as usize..=(q_max * chosen).ceil() as usize);
+        let d = destroy.select(&mut rand);
+        let mut destroyed = match d {
+            0 => lns::random_drop(&s, q, &mut rand),
+            _ => lns::worst_drop(&s, q),
+        };
+        let r = repair.select(&mut rand);
+        let s_prime = match r {
+            0 => {
+                repair::repair(&mut destroyed);
+                repair::fill(&mut destroyed);
+                destroyed
+            }
+            _ => {
+                repair::repair(&mut destroyed);
+                construction::grasp(&destroyed, 1.0, &mut rand)
+            }
+        };
+
+        let outcome = if s_prime > s_best {
+            Outcome::NewBest
+        } else if s_prime > s {
+            Outcome::Improved
+        } else if rand.gen::<f64>() < E.powf((s_prime.value() - s.value()).0 / temp) {
+            Outcome::Accepted
+        } else {
+            Outcome::Rejected
+        };
+        destroy.reward(d, outcome);
+        repair.reward(r, outcome);
+
+        if outcome == Outcome::NewBest {
+            s_best = s_prime.clone();
+        }
+        if outcome != Outcome::Rejected {
+            s = s_prime;
+        }
+
+        if i % segment == 0 {
+            destroy.end_segment();
+            repair.end_segment();
+        }
+        temp *= alpha;
+    }
+    let runtime = now.elapsed();
+
+    (runtime, s_best.total_value())
+}
+
+fn run_all<S: KnapsackSolution>(s0: &S, pparams: PParams) {
+    println!("{}", debug_to_kw(&pparams));
+    let mut destroy = AdaptiveWeights::new(&DESTROY);
+    let mut repair = AdaptiveWeights::new(&REPAIR);
+    println!("runtime;value");
+    for _ in 0..10 {
+        let (runtime, objective_func) = run(s0, pparams, &mut destroy, &mut repair);
+        println!("{:?};{}", runtime.as_secs_f64(), objective_func);
+    }
+    // estatísticas acumuladas em todas as execuções, com os pesos da última
+    destroy.print_stats();
+    repair.print_stats();
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let pparams = PParams {
+        i_max: 2000,
+        segment: 100,
+        q_min: 0.1,
+        q_max: 0.4,
+        temp0: 10.0,
+        alpha: 0.998,
+        penalty: 2.into(),
+    };
+
+    // Instâncias multidimensionais no formato da OR-Library
+    if has_flag("--mknap") {
+        let problem = flag_value("--problem").map_or(Ok(0), |p| p.parse())?;
+        let instance = &read_mknap(&mut open_file())?[problem];
+        let evaluation = if has_flag("--repair") {
+            multidim::Evaluation::SurrogateRepair
+        } else {
+            multidim::Evaluation::Penalty(pparams.penalty)
+        };
+        // Solução inicial é gulosa.
+        run_all(&multidim::Solution::greedy(instance, evaluation), pparams);
+        return Ok(());
+    }
+
+    // Instâncias da mochila quadrática
+    if has_flag("--qkp") {
+        let instance = read_qkp(&mut open_file())?;
+        let params = WithPenalty {
+            max_weight: instance.capacity,
+            penalty: pparams.penalty,
+        };
+        // Solução inicial é gulosa.
+        run_all(&quadratic::Solution::greedy(&instance, params), pparams);
+        return Ok(());
+    }
+
+    let (maxw, items) = read_knapsack(&mut open_file())?;
+    let params = WithPenalty {
+        max_weight: maxw,
+        penalty: pparams.penalty,
+    };
+    // Solução inicial é gulosa.
+    run_all(&Solution::greedy(&items, params), pparams);
+    Ok(())
+}
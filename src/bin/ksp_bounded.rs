@@ -0,0 +1,69 @@
+use exs::annealing::{Annealing, Cooling, Schedule};
+use exs::knapsack::bounded::{dynamic_programming, read_bounded_knapsack, Solution};
+use exs::knapsack::{UWeight, WithPenalty};
+use exs::{debug_to_kw, open_file};
+use std::time::Instant;
+
+/// Recozimento simulado que incrementa ou decrementa um ítem aleatório a cada iteração.
+fn anneal<'ks>(s0: &Solution<'ks>, schedule: Schedule, temp0: f64) -> Solution<'ks> {
+    let mut rand = rand::thread_rng();
+    let mut s = s0.clone();
+    let mut s_best = s0.clone();
+    let mut annealing = Annealing::new(schedule, temp0);
+    while !annealing.is_frozen() {
+        while !annealing.stage_done() {
+            let Some(mv) = s.random_move(&mut rand) else {
+                return s_best;
+            };
+            if annealing.accept(-s.move_delta(mv).0, &mut rand) {
+                s.apply_in_place(mv);
+                if s > s_best && s.is_feasible() {
+                    s_best = s.clone();
+                    annealing.improved();
+                }
+            }
+        }
+        annealing.next_stage();
+    }
+    s_best
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (maxw, items) = read_bounded_knapsack(&mut open_file())?;
+    let items = &*items;
+
+    let params = WithPenalty {
+        max_weight: maxw,
+        penalty: UWeight::from(1.0),
+    };
+    let schedule = Schedule {
+        cooling: Cooling::Geometric,
+        alpha: 0.999,
+        epsilon: 0.005,
+        i_max: 10,
+        accepted_max: 0,
+        stages_max: 0,
+        reheat_after: 0,
+        reheat_factor: 1.0,
+        reheats_max: 0,
+    };
+
+    println!("{}", debug_to_kw(&schedule));
+    println!("method;runtime;value");
+    let now = Instant::now();
+    let s = Solution::greedy(items, params);
+    println!(
+        "greedy;{:?};{}",
+        now.elapsed().as_secs_f64(),
+        s.total_value()
+    );
+
+    let now = Instant::now();
+    let s = anneal(&s, schedule, 100.0);
+    println!("sa;{:?};{}", now.elapsed().as_secs_f64(), s.total_value());
+
+    let now = Instant::now();
+    let s = dynamic_programming(items, params);
+    println!("dp;{:?};{}", now.elapsed().as_secs_f64(), s.total_value());
+    Ok(())
+}
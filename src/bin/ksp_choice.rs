@@ -0,0 +1,89 @@
+use std::f64::consts::E;
+use std::time::Instant;
+
+use exs::knapsack::choice::{dynamic_programming, read_choice_knapsack, Solution};
+use exs::knapsack::{UWeight, WithPenalty};
+use exs::{debug_to_kw, open_file};
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PParams {
+    pub i_max: usize,
+    pub epsilon: f64,
+    pub alpha: f64,
+    pub temp0: f64,
+    pub penalty: UWeight,
+}
+
+fn sa<'ks>(s0: &Solution<'ks>, pparams: PParams) -> Solution<'ks> {
+    let PParams {
+        i_max,
+        epsilon,
+        alpha,
+        temp0: mut temp,
+        ..
+    } = pparams;
+
+    let mut s = s0.clone();
+    let mut s_best = s.clone();
+
+    let mut rand = rand::thread_rng();
+    while temp > epsilon {
+        for _ in 0..i_max {
+            // sem classes com mais de um ítem, a solução inicial é a única
+            let Some(s_prime) = s.random_neighbour(&mut rand) else {
+                return s_best;
+            };
+
+            if s_prime > s {
+                s = s_prime;
+                if s > s_best && s.is_feasible() {
+                    s_best = s.clone();
+                }
+            } else if rand.gen::<f64>() < E.powf(*(s_prime.value - s.value) / temp) {
+                s = s_prime;
+            }
+        }
+        temp *= alpha;
+    }
+    s_best
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (maxw, classes) = read_choice_knapsack(&mut open_file())?;
+    let classes = &*classes;
+
+    let pparams = PParams {
+        epsilon: 0.005,
+        i_max: 10,
+        temp0: 1000.0,
+        alpha: 0.9995,
+        penalty: 2.into(),
+    };
+    let params = WithPenalty {
+        max_weight: maxw,
+        penalty: pparams.penalty,
+    };
+    println!("{}", debug_to_kw(&pparams));
+
+    println!("method;runtime;value");
+    let now = Instant::now();
+    let greedy = Solution::greedy(classes, params);
+    println!(
+        "greedy;{:?};{}",
+        now.elapsed().as_secs_f64(),
+        greedy.total_value()
+    );
+
+    // Solução inicial é gulosa.
+    let now = Instant::now();
+    let s = sa(&greedy, pparams);
+    println!("sa;{:?};{}", now.elapsed().as_secs_f64(), s.total_value());
+
+    let now = Instant::now();
+    match dynamic_programming(classes, params) {
+        Some(s) => println!("dp;{:?};{}", now.elapsed().as_secs_f64(), s.total_value()),
+        None => println!("dp;{:?};inviável", now.elapsed().as_secs_f64()),
+    }
+    Ok(())
+}
@@ -1,8 +1,8 @@
-use bitvec::bitvec;
+use exs::grasp::ReactiveAlpha;
+use exs::knapsack::multidim::{self, read_mknap};
 use exs::knapsack::{Solution, *};
-use exs::{debug_to_kw, open_file};
-use itertools::Itertools;
-use rand::seq::SliceRandom;
+use exs::relinking::{path_relinking, Direction, ElitePool};
+use exs::{debug_to_kw, flag_value, has_flag, open_file};
 use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Copy)]
@@ -11,75 +11,67 @@ pub struct PParams {
     pub idle_max: usize,
     pub a: f64,
     penalty: UWeight,
+    pub neighbourhood: Neighbourhood,
+    /// Se a construção sorteia pela razão valor/peso em vez do ganho na avaliação.
+    pub by_ratio: bool,
+    /// Sentido do religamento de caminhos com as soluções de elite, se houver.
+    pub relinking: Option<Direction>,
+    pub elite_size: usize,
+    pub elite_distance: usize,
+    /// Se `a` é sorteado de `ALPHAS` com probabilidades reajustadas a cada `reactive_period`
+    /// iterações, em vez de fixo. As probabilidades são mantidas entre as execuções.
+    pub reactive: bool,
+    pub reactive_period: usize,
 }
 
-// g é a melhora em relação a solução atual
-fn eval_candidate(s: &Solution, s_prime: &Solution) -> Weight {
-    s_prime.value - s.value
-}
-
-fn random_greedy_solution<'g>(
-    knapsack: &'g [Item],
-    a: f64,
-    rand: &mut impl rand::Rng,
-    params: WithPenalty,
-) -> Solution<'g> {
-    // solução inicial vazia
-    let mut s = Solution::new(knapsack, bitvec![0; knapsack.len()], params);
-
-    let mut lc = knapsack.len();
-
-    while lc != 0 {
-        // Cria a lrc com candidatos e seus g's
-        let mut lrc = (0..knapsack.len())
-            .into_iter()
-            .map(|flip| s.flip(flip))
-            .map(|flipped| {
-                let g = eval_candidate(&s, &flipped);
-                (flipped, g)
-            })
-            .collect_vec();
-        // ordena por valor de g
-        lrc.sort_unstable_by_key(|k| k.1);
-        let worst = lrc[0].1;
-        let best = lrc[lrc.len() - 1].1;
-        let cutoff = Weight::from(a) * (best - worst) + worst;
-
-        // Remove candidatos cujo g não atende os parametros
-        // nesse caso é >= pois é um problema de max
-        lrc.retain(|(_, weight)| *weight >= cutoff);
-
-        // escolhe candidato aleatorialemente
-        let (flipped, _) = lrc.choose_mut(rand).unwrap();
-        // remove candidato da LC
-        lc -= 1;
-
-        // s = flipped;
-        std::mem::swap(&mut s, flipped);
+/// Valores de `a` experimentados pelo GRASP reativo. Como o corte é `pior + a · (melhor - pior)`,
+/// o guloso puro (`a = 1`) fica de fora, pois sempre constrói a mesma solução, assim como o
+/// aleatório puro (`a = 0`).
+const ALPHAS: [f64; 10] = [0.05, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9];
+
+/// Repara a solução e aplica a busca de remoção e adição, seguida de melhor melhora na
+/// vizinhança escolhida, aceitando só movimentos que mantêm a solução viável. Com a vizinhança
+/// de inversões, é só a busca de remoção e adição.
+fn local_search<S: KnapsackSolution>(s: &mut S, neighbourhood: Neighbourhood) {
+    repair::repair(s);
+    repair::drop_add_search(s);
+    if neighbourhood == Neighbourhood::Flip {
+        return;
     }
-    s
-}
-
-fn greedy_search(s: &mut Solution) {
-    let mut sorted = s.knapsack.iter().enumerate().collect::<Vec<_>>();
-    // ordena por valor do ítem
-    sorted.sort_by(|(_, item), (_, item2)| item.value.total_cmp(&item2.value));
-
-    while let Some((i, _)) = sorted.pop() {
-        let s_prime = s.flip(i);
-
-        if s_prime > *s && s_prime.total_weight() <= s.total_weight() {
-            *s = s_prime
+    loop {
+        let best = s
+            .moves(neighbourhood)
+            .filter(|mv| s.move_is_feasible(mv))
+            .map(|mv| (s.move_delta(&mv), mv))
+            .max_by_key(|(delta, _)| *delta);
+        match best {
+            Some((delta, mv)) if delta.0 > 0.0 => s.apply(&mv),
+            // ótimo local
+            _ => break,
         }
     }
 }
 
-fn run(knapsack: &[Item], pparams: PParams, params: WithPenalty) -> (Duration, UWeight) {
+fn run<S: KnapsackSolution>(
+    empty: &S,
+    pparams: PParams,
+    mut reactive: Option<&mut ReactiveAlpha>,
+) -> (Duration, UWeight) {
     let PParams {
-        i_max, a, idle_max, ..
+        i_max,
+        a,
+        idle_max,
+        neighbourhood,
+        by_ratio,
+        relinking,
+        elite_size,
+        elite_distance,
+        reactive_period,
+        ..
     } = pparams;
 
     let mut s_best = None;
+    let mut elite = ElitePool::new(elite_size, elite_distance);
 
     let mut rand = rand::thread_rng();
 
@@ -87,8 +79,45 @@ fn run(knapsack: &[Item], pparams: PParams, params: WithPenalty) -> (Duration, U
 
     let now = Instant::now();
     for i in 0.. {
-        let mut s = random_greedy_solution(knapsack, a, &mut rand, params);
-        greedy_search(&mut s);
+        // no modo reativo, sorteia o `a` desta iteração
+        let (alpha, a) = match reactive.as_deref() {
+            Some(reactive) => {
+                let (index, alpha) = reactive.select(&mut rand);
+                (Some(index), alpha)
+            }
+            None => (None, a),
+        };
+        let mut s = if by_ratio {
+            construction::randomized_ratio(empty, a, &mut rand)
+        } else {
+            construction::grasp(empty, a, &mut rand)
+        };
+        local_search(&mut s, neighbourhood);
+
+        if let Some(direction) = relinking {
+            // religa o ótimo local com uma solução de elite e busca a partir do melhor ponto
+            // do caminho
+            let guide = elite.choose(&mut rand).cloned();
+            let relinked = guide
+                .and_then(|guide| path_relinking(&s, &guide, direction))
+                .map(|mut relinked| {
+                    local_search(&mut relinked, neighbourhood);
+                    relinked
+                });
+            if s.is_feasible() {
+                elite.insert(&s);
+            }
+            if let Some(relinked) = relinked.filter(|r| r.is_feasible()) {
+                elite.insert(&relinked);
+                if relinked > s {
+                    s = relinked;
+                }
+            }
+        }
+
+        if let (Some(reactive), Some(index)) = (reactive.as_deref_mut(), alpha) {
+            reactive.record(index, s.total_value().0);
+        }
 
         // Na primeira iteração não há uma solução melhor ainda
         let Some(ref mut s_best) = s_best else {
@@ -98,13 +127,19 @@ fn run(knapsack: &[Item], pparams: PParams, params: WithPenalty) -> (Duration, U
             continue;
         };
 
-        if s > *s_best && s.total_weight() <= params.max_weight {
+        if s > *s_best && s.is_feasible() {
             *s_best = s;
             idle = 0;
         } else {
             idle += 1;
         }
 
+        if let Some(reactive) = reactive.as_deref_mut() {
+            if (i + 1) % reactive_period == 0 {
+                reactive.update(s_best.total_value().0);
+            }
+        }
+
         // se idle_max != 0, quer dizer que estamos limitando por iterações sem melhoria
         if idle_max != 0 && idle >= idle_max {
             // quantidade de turnos sem melhora excedeu o parâmetro.
@@ -122,11 +157,26 @@ fn run(knapsack: &[Item], pparams: PParams, params: WithPenalty) -> (Duration, U
     (runtime, s_best.unwrap().total_value())
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let (maxw, items) = read_knapsack(&mut open_file())?;
-    let items = &*items;
+fn run_all<S: KnapsackSolution>(empty: &S, pparams: PParams) {
+    println!("{}", debug_to_kw(&pparams));
+    let mut reactive = pparams.reactive.then(|| ReactiveAlpha::new(&ALPHAS, false));
+
+    println!("runtime;value");
+    for _ in 0..10 {
+        let (runtime, objective_func) = run(empty, pparams, reactive.as_mut());
+        println!("{:?};{}", runtime.as_secs_f64(), objective_func);
+    }
+    // distribuição de `a` acumulada ao longo de todas as execuções
+    if let Some(reactive) = reactive {
+        reactive.print_distribution();
+    }
+}
 
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     let alt = false;
+    let relinking = flag_value("--relink").map(|d| d.parse()).transpose()?;
+    let neighbourhood =
+        flag_value("--neighbourhood").map_or(Ok(Neighbourhood::Flip), |n| n.parse())?;
 
     let pparams = if alt {
         PParams {
@@ -134,6 +184,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             idle_max: 0,
             penalty: 1.into(),
             a: 0.60,
+            neighbourhood,
+            by_ratio: has_flag("--ratio"),
+            relinking,
+            elite_size: 10,
+            elite_distance: 2,
+            reactive: has_flag("--reactive"),
+            reactive_period: 10,
         }
     } else {
         PParams {
@@ -141,19 +198,37 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             idle_max: 80,
             penalty: 2.into(),
             a: 0.20,
+            neighbourhood,
+            by_ratio: has_flag("--ratio"),
+            relinking,
+            elite_size: 10,
+            elite_distance: 2,
+            reactive: has_flag("--reactive"),
+            reactive_period: 10,
         }
     };
 
+    // Instâncias multidimensionais no formato da OR-Library
+    if has_flag("--mknap") {
+        let problem = flag_value("--problem").map_or(Ok(0), |p| p.parse())?;
+        let instance = &read_mknap(&mut open_file())?[problem];
+        let evaluation = if has_flag("--repair") {
+            multidim::Evaluation::SurrogateRepair
+        } else {
+            multidim::Evaluation::Penalty(pparams.penalty)
+        };
+        run_all(&multidim::Solution::empty(instance, evaluation), pparams);
+        return Ok(());
+    }
+
+    let (maxw, items) = read_knapsack(&mut open_file())?;
+    let items = &*items;
+
     let params = WithPenalty {
         max_weight: maxw,
-        penalty: pparams.penalty.into(),
+        penalty: pparams.penalty,
     };
 
-    println!("{}", debug_to_kw(&pparams));
-    println!("runtime;value");
-    for _ in 0..10 {
-        let (runtime, objective_func) = run(items, pparams, params);
-        println!("{:?};{}", runtime.as_secs_f64(), objective_func);
-    }
+    run_all(&Solution::empty(items, params), pparams);
     Ok(())
 }
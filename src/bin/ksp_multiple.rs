@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use std::f64::consts::E;
+use std::time::{Duration, Instant};
+
+use exs::knapsack::multiple::{read_multiple_knapsack, with_penalty, Move, Solution};
+use exs::knapsack::UWeight;
+use exs::{debug_to_kw, has_flag, open_file};
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SaParams {
+    pub i_max: usize,
+    pub epsilon: f64,
+    pub alpha: f64,
+    pub temp0: f64,
+    pub penalty: UWeight,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TabuParams {
+    pub i_max: usize,
+    pub tabu_memory: usize,
+    pub penalty: UWeight,
+}
+
+fn sa(s0: &Solution, pparams: SaParams) -> (Duration, UWeight) {
+    let SaParams {
+        i_max,
+        epsilon,
+        alpha,
+        temp0: mut temp,
+        ..
+    } = pparams;
+
+    let mut s = s0.clone();
+    let mut s_best = s.clone();
+
+    let mut rand = rand::thread_rng();
+
+    let now = Instant::now();
+    while temp > epsilon {
+        for _ in 0..i_max {
+            // sem movimentos possíveis, a solução não muda
+            let Some(mv) = s.random_move(&mut rand) else {
+                return (now.elapsed(), s_best.total_value());
+            };
+            let delta = s.move_delta(mv);
+
+            if delta.0 > 0.0 {
+                s.apply_in_place(mv);
+                if s > s_best && s.is_feasible() {
+                    s_best = s.clone();
+                }
+            } else if rand.gen::<f64>() < E.powf(delta.0 / temp) {
+                s.apply_in_place(mv);
+            }
+        }
+        temp *= alpha;
+    }
+
+    (now.elapsed(), s_best.total_value())
+}
+
+/// Ítens movidos recentemente e por quantas iterações ainda estão banidos.
+type TabuList = BTreeMap<usize, usize>;
+
+/// Melhor movimento permitido e sua variação na avaliação.
+fn next_neighbour(s: &Solution, tabu: &TabuList, s_best: &Solution) -> Option<(Move, UWeight)> {
+    let mut best_neighbour: Option<(Move, UWeight)> = None;
+    for mv in s.moves() {
+        let delta = s.move_delta(mv);
+        // aspiração: movimento é uma melhora absoluta
+        if s.value + delta > s_best.value && s.move_is_feasible(mv) {
+            return Some((mv, delta));
+        }
+
+        // algum dos ítens está banido, olhar o próximo
+        if mv.items().any(|i| tabu.contains_key(&i)) {
+            continue;
+        }
+
+        match best_neighbour {
+            Some((_, best)) if delta <= best => (),
+            _ => best_neighbour = Some((mv, delta)),
+        }
+    }
+    best_neighbour
+}
+
+fn tabu(s0: &Solution, pparams: TabuParams) -> (Duration, UWeight) {
+    let TabuParams {
+        i_max, tabu_memory, ..
+    } = pparams;
+
+    let mut s = s0.clone();
+    let mut s_best = s.clone();
+    let mut tabu = TabuList::new();
+
+    let now = Instant::now();
+    for _ in 0..i_max {
+        let Some((mv, _)) = next_neighbour(&s, &tabu, &s_best) else {
+            // todos os movimentos estavam banidos, esquece o ítem banido há mais tempo; sem
+            // ítens banidos, não há movimentos e a busca termina
+            let Some((&oldest, _)) = tabu.iter().min_by_key(|(_, turns)| **turns) else {
+                break;
+            };
+            tabu.remove(&oldest);
+            continue;
+        };
+        // continua a busca a partir da solução encontrada
+        s.apply_in_place(mv);
+
+        if s > s_best && s.is_feasible() {
+            s_best = s.clone();
+        }
+
+        tabu.retain(|_item, tabu_turns| {
+            *tabu_turns -= 1;
+            // remove ítens cujo tempo de vida se tornou 0
+            *tabu_turns != 0
+        });
+        for item in mv.items() {
+            tabu.insert(item, tabu_memory);
+        }
+    }
+
+    (now.elapsed(), s_best.total_value())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (capacities, items) = read_multiple_knapsack(&mut open_file())?;
+    let items = &*items;
+
+    let (runtime, objective_func) = if has_flag("--tabu") {
+        let pparams = TabuParams {
+            i_max: 1000,
+            tabu_memory: 10,
+            penalty: 3.into(),
+        };
+        println!("{}", debug_to_kw(&pparams));
+        let eval_methods = with_penalty(&capacities, pparams.penalty);
+        // Solução inicial é gulosa.
+        tabu(&Solution::greedy(items, &eval_methods), pparams)
+    } else {
+        let pparams = SaParams {
+            epsilon: 0.005,
+            i_max: 10,
+            temp0: 1000.0,
+            alpha: 0.9995,
+            penalty: 2.into(),
+        };
+        println!("{}", debug_to_kw(&pparams));
+        let eval_methods = with_penalty(&capacities, pparams.penalty);
+        // Solução inicial é gulosa.
+        sa(&Solution::greedy(items, &eval_methods), pparams)
+    };
+    println!("runtime;value");
+    println!("{:?};{}", runtime.as_secs_f64(), objective_func);
+    Ok(())
+}
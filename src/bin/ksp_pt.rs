@@ -0,0 +1,118 @@
+use exs::knapsack::multidim::{self, read_mknap};
+use exs::knapsack::quadratic::{self, read_qkp};
+use exs::knapsack::{read_knapsack, repair, KnapsackSolution, Neighbourhood, Solution, Weight};
+use exs::knapsack::{AdaptivePenalty, StrategicOscillation, UWeight, WithPenalty};
+use exs::tempering::{geometric_ladder, Tempering};
+use exs::{debug_to_kw, flag_value, has_flag, open_file};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+pub struct PParams {
+    /// Quantidade de réplicas, uma por thread.
+    pub replicas: usize,
+    pub temp_min: f64,
+    pub temp_max: f64,
+    pub rounds: usize,
+    /// Passos de Metropolis de cada réplica entre duas rodadas de trocas.
+    pub sweep: usize,
+    pub penalty: UWeight,
+    pub neighbourhood: Neighbourhood,
+}
+
+fn run<S: KnapsackSolution + Send>(
+    s0: &S,
+    tempering: &mut Tempering,
+    pparams: PParams,
+) -> (Duration, Weight) {
+    let now = Instant::now();
+    // nenhuma réplica viável: decodifica a solução inicial
+    let s_best = tempering
+        .run(s0, pparams.neighbourhood)
+        .unwrap_or_else(|| repair::decode(s0));
+    let runtime = now.elapsed();
+
+    (runtime, s_best.total_value())
+}
+
+fn run_all<S: KnapsackSolution + Send>(s0: &S, pparams: PParams) {
+    println!("{}", debug_to_kw(&pparams));
+
+    let temps = geometric_ladder(pparams.temp_min, pparams.temp_max, pparams.replicas);
+    let mut tempering = Tempering::new(temps, pparams.rounds, pparams.sweep);
+
+    println!("runtime;value");
+    for _ in 0..10 {
+        let (runtime, objective_func) = run(s0, &mut tempering, pparams);
+        println!("{:?};{}", runtime.as_secs_f64(), objective_func);
+    }
+    // taxas de troca acumuladas entre as execuções
+    tempering.print_stats();
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // por padrão, uma réplica por núcleo, mas ao menos 4 para que haja trocas
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get().max(4));
+    let replicas = flag_value("--replicas").map_or(Ok(threads), |r| r.parse())?;
+    if replicas == 0 {
+        return Err("É preciso ao menos uma réplica".into());
+    }
+    let pparams = PParams {
+        replicas,
+        temp_min: 1.0,
+        temp_max: 100.0,
+        rounds: 500,
+        sweep: 100,
+        penalty: 2.into(),
+        neighbourhood: flag_value("--neighbourhood")
+            .map_or(Ok(Neighbourhood::Flip), |n| n.parse())?,
+    };
+
+    // Instâncias multidimensionais no formato da OR-Library
+    if has_flag("--mknap") {
+        let problem = flag_value("--problem").map_or(Ok(0), |p| p.parse())?;
+        let instance = &read_mknap(&mut open_file())?[problem];
+        let evaluation = if has_flag("--repair") {
+            multidim::Evaluation::SurrogateRepair
+        } else {
+            multidim::Evaluation::Penalty(pparams.penalty)
+        };
+        // Solução inicial é gulosa.
+        run_all(&multidim::Solution::greedy(instance, evaluation), pparams);
+        return Ok(());
+    }
+
+    // Instâncias da mochila quadrática
+    if has_flag("--qkp") {
+        let instance = read_qkp(&mut open_file())?;
+        let params = WithPenalty {
+            max_weight: instance.capacity,
+            penalty: pparams.penalty,
+        };
+        // Solução inicial é gulosa.
+        run_all(&quadratic::Solution::greedy(&instance, params), pparams);
+        return Ok(());
+    }
+
+    let (maxw, items) = read_knapsack(&mut open_file())?;
+    let items = &*items;
+
+    // Solução inicial é gulosa.
+    match flag_value("--penalty").as_deref() {
+        Some("adaptive") => {
+            let params = AdaptivePenalty::new(maxw, pparams.penalty);
+            run_all(&Solution::greedy(items, params), pparams);
+        }
+        Some("oscillation") => {
+            let params = StrategicOscillation::new(maxw, pparams.penalty);
+            run_all(&Solution::greedy(items, params), pparams);
+        }
+        _ => {
+            let params = WithPenalty {
+                max_weight: maxw,
+                penalty: pparams.penalty,
+            };
+            run_all(&Solution::greedy(items, params), pparams);
+        }
+    }
+    Ok(())
+}
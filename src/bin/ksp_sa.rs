@@ -1,92 +1,221 @@
-use std::f64::consts::E;
 use std::time::{Duration, Instant};
 
-use exs::debug_to_kw;
-use exs::knapsack::{Item, Weight};
+use exs::annealing::{initial_temperature, Annealing, Cooling, Schedule};
+use exs::knapsack::multidim::{self, read_mknap};
+use exs::knapsack::quadratic::{self, read_qkp};
+use exs::knapsack::repair;
+use exs::knapsack::{
+    AdaptivePenalty, KnapsackSolution, Neighbourhood, StrategicOscillation, Weight,
+};
+use exs::{debug_to_kw, flag_value, has_flag};
 
 use exs::{
     knapsack::{read_knapsack, Solution, WithPenalty},
     open_file,
 };
-use rand::Rng;
 
 #[derive(Debug, Clone, Copy)]
 pub struct PParams {
-    pub i_max: usize,
-    pub epsilon: f64,
-    pub alpha: f64,
     pub temp0: f64,
-    pub exponential_cooling: bool,
+    /// Se diferente de 0, `temp0` é calculada para aceitar essa fração das pioras amostradas na
+    /// solução inicial.
+    pub initial_acceptance: f64,
+    pub samples: usize,
     pub penalty: Weight,
+    pub neighbourhood: Neighbourhood,
+    /// Se soluções inviáveis são reparadas e completadas antes de serem comparadas com a melhor.
+    pub decode: bool,
 }
 
-fn run(knapsack: &[Item], params: WithPenalty, pparams: PParams) -> (Duration, Weight) {
+/// Executa o SA, devolvendo também a fração das iterações em que a solução corrente era
+/// inviável.
+fn run<S: KnapsackSolution>(
+    s0: &S,
+    schedule: Schedule,
+    pparams: PParams,
+) -> Result<(Duration, Weight, f64), String> {
     let PParams {
-        i_max,
-        epsilon,
-        alpha,
-        temp0: mut temp,
-        exponential_cooling,
+        temp0,
+        initial_acceptance,
+        samples,
+        neighbourhood,
+        decode,
         ..
     } = pparams;
 
-    // Solução inicial é gulosa.
-    let mut s = Solution::greedy(knapsack, params);
-    let mut s_best = s.clone();
+    let mut s = s0.clone();
+    // a gulosa pode ser inviável com penalidades fracas; a melhor começa viável
+    let mut s_best = repair::decode(&s);
 
     let mut rand = rand::thread_rng();
 
+    let (mut iterations, mut infeasible) = (0, 0);
     let now = Instant::now();
-    while temp > epsilon {
-        for _ in 0..i_max {
-            let random_index = rand.gen_range(0..knapsack.len());
-            let s_prime = s.flip(random_index);
-
-            if s_prime > s {
-                s = s_prime;
-                if s > s_best {
+    let temp0 = if initial_acceptance != 0.0 {
+        // amostra vizinhos da solução inicial; como é um problema de max, a piora é `-delta`
+        let worsenings =
+            (0..samples).map(|_| -s.move_delta(&s.random_move(neighbourhood, &mut rand)).0);
+        initial_temperature(worsenings, initial_acceptance)?.unwrap_or(temp0)
+    } else {
+        temp0
+    };
+    let mut annealing = Annealing::new(schedule, temp0);
+
+    while !annealing.is_frozen() {
+        while !annealing.stage_done() {
+            let mv = s.random_move(neighbourhood, &mut rand);
+            let delta = s.move_delta(&mv);
+
+            if annealing.accept(-delta.0, &mut rand) {
+                s.apply(&mv);
+                if s > s_best && s.is_feasible() {
                     s_best = s.clone();
+                    annealing.improved();
                 }
-            } else if rand.gen::<f64>() < E.powf(*(s.value - s_prime.value) / temp) {
-                s = s_prime;
             }
-        }
 
-        if exponential_cooling {
-            temp *= alpha;
-        } else {
-            temp -= alpha;
+            iterations += 1;
+            if !s.is_feasible() {
+                infeasible += 1;
+                if decode {
+                    let decoded = repair::decode(&s);
+                    if decoded > s_best {
+                        s_best = decoded;
+                        annealing.improved();
+                    }
+                }
+            }
+            s.adapt_penalty();
         }
+        annealing.next_stage();
     }
 
     let runtime = now.elapsed();
 
-    (runtime, s_best.total_value())
+    Ok((
+        runtime,
+        s_best.total_value(),
+        infeasible as f64 / iterations as f64,
+    ))
+}
+
+fn run_all<S: KnapsackSolution>(
+    s0: &S,
+    schedule: Schedule,
+    pparams: PParams,
+) -> Result<(), String> {
+    println!("{};{}", debug_to_kw(&schedule), debug_to_kw(&pparams));
+    println!("runtime;value;infeasible");
+    for _ in 0..10 {
+        let (runtime, objective_func, infeasible) = run(s0, schedule, pparams)?;
+        println!(
+            "{:?};{};{}",
+            runtime.as_secs_f64(),
+            objective_func,
+            infeasible
+        );
+    }
+    Ok(())
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let (maxw, items) = read_knapsack(&mut open_file())?;
-    let items = &*items;
+    let cooling = flag_value("--cooling").map_or(Ok(Cooling::Linear), |c| c.parse())?;
+    let alpha = match flag_value("--alpha") {
+        Some(alpha) => alpha.parse()?,
+        None => match cooling {
+            Cooling::Linear => 0.9995,
+            Cooling::Geometric => 0.999,
+            Cooling::Logarithmic => 0.0,
+            Cooling::LundyMees => 0.01,
+            Cooling::Adaptive => 0.7,
+        },
+    };
+    let equilibrium = has_flag("--equilibrium");
+    let reheat = has_flag("--reheat");
 
-    let pparams = PParams {
+    let schedule = Schedule {
+        cooling,
+        alpha,
         epsilon: 0.005,
-        i_max: 10,
+        // no equilíbrio, estágios longos que terminam cedo em temperaturas altas
+        i_max: if equilibrium { 100 } else { 10 },
+        accepted_max: if equilibrium { 10 } else { 0 },
+        stages_max: flag_value("--stages").map_or(
+            Ok(if cooling == Cooling::Logarithmic {
+                10000
+            } else {
+                0
+            }),
+            |s| s.parse(),
+        )?,
+        reheat_after: if reheat { 200 } else { 0 },
+        reheat_factor: 10.0,
+        reheats_max: 5,
+    };
+    let pparams = PParams {
         temp0: 1000.0,
-        alpha: 0.9995,
-        exponential_cooling: false,
+        initial_acceptance: if has_flag("--auto-temp") { 0.8 } else { 0.0 },
+        samples: 100,
         penalty: 2.into(),
+        neighbourhood: flag_value("--neighbourhood")
+            .map_or(Ok(Neighbourhood::Flip), |n| n.parse())?,
+        decode: has_flag("--decode"),
     };
 
-    let params = WithPenalty {
-        max_weight: maxw,
-        penalty: pparams.penalty,
-    };
+    // Instâncias multidimensionais no formato da OR-Library
+    if has_flag("--mknap") {
+        let problem = flag_value("--problem").map_or(Ok(0), |p| p.parse())?;
+        let instance = &read_mknap(&mut open_file())?[problem];
+        let evaluation = if has_flag("--repair") {
+            multidim::Evaluation::SurrogateRepair
+        } else {
+            multidim::Evaluation::Penalty(pparams.penalty)
+        };
+        // Solução inicial é gulosa.
+        run_all(
+            &multidim::Solution::greedy(instance, evaluation),
+            schedule,
+            pparams,
+        )?;
+        return Ok(());
+    }
 
-    println!("{}", debug_to_kw(&pparams));
-    println!("runtime;value");
-    for _ in 0..10 {
-        let (runtime, objective_func) = run(items, params, pparams);
-        println!("{:?};{}", runtime.as_secs_f64(), objective_func);
+    // Instâncias da mochila quadrática
+    if has_flag("--qkp") {
+        let instance = read_qkp(&mut open_file())?;
+        let params = WithPenalty {
+            max_weight: instance.capacity,
+            penalty: pparams.penalty,
+        };
+        // Solução inicial é gulosa.
+        run_all(
+            &quadratic::Solution::greedy(&instance, params),
+            schedule,
+            pparams,
+        )?;
+        return Ok(());
+    }
+
+    let (maxw, items) = read_knapsack(&mut open_file())?;
+    let items = &*items;
+
+    // Solução inicial é gulosa.
+    match flag_value("--penalty").as_deref() {
+        Some("adaptive") => {
+            let params = AdaptivePenalty::new(maxw, pparams.penalty);
+            run_all(&Solution::greedy(items, params), schedule, pparams)?;
+        }
+        Some("oscillation") => {
+            let params = StrategicOscillation::new(maxw, pparams.penalty);
+            run_all(&Solution::greedy(items, params), schedule, pparams)?;
+        }
+        _ => {
+            let params = WithPenalty {
+                max_weight: maxw,
+                penalty: pparams.penalty,
+            };
+            run_all(&Solution::greedy(items, params), schedule, pparams)?;
+        }
     }
     Ok(())
 }
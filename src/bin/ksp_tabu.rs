@@ -1,87 +1,117 @@
-use exs::knapsack::{read_knapsack, Item, Solution, UWeight, WithPenalty};
-use exs::{debug_to_kw, open_file};
-use std::collections::BTreeMap;
+use exs::knapsack::multidim::{self, read_mknap};
+use exs::knapsack::quadratic::{self, read_qkp};
+use exs::knapsack::repair;
+use exs::knapsack::{
+    read_knapsack, AdaptivePenalty, KnapsackSolution, Move, Neighbourhood, Solution,
+    StrategicOscillation, UWeight, WithPenalty,
+};
+use exs::tabu::{self, Aspiration, TabuMemory, TabuParams, Tenure};
+use exs::{debug_to_kw, flag_value, has_flag, open_file};
 use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub struct PParams {
     pub i_max: usize,
-    pub tabu_memory: usize,
     pub idle_max: usize,
     pub penalty: UWeight,
+    pub neighbourhood: Neighbourhood,
+    /// Se soluções inviáveis são reparadas e completadas antes de serem comparadas com a melhor.
+    pub decode: bool,
 }
 
-type TabuList = BTreeMap<usize, usize>;
-
-fn next_neighbour<'g>(
-    s: &Solution<'g>,
-    tabu: &TabuList,
-    s_best: &Solution<'g>,
-    max_weight: UWeight,
-) -> Option<(usize, Solution<'g>)> {
-    let all_flips = 0..s.knapsack.len();
-    let mut best_neighbour: Option<(usize, Solution<'g>)> = None;
-    for flip in all_flips {
-        let s_prime = s.flip(flip);
-        // aspiração: troca é uma melhora absoluta
-        if s_prime > *s_best && s_prime.total_weight() <= max_weight {
-            return Some((flip, s_prime));
-        }
-
-        // troca está banida, olhar o próximo
-        if tabu.get(&flip).is_some() {
+fn next_neighbour<S: KnapsackSolution>(
+    s: &S,
+    tabu: &TabuMemory,
+    s_best: &S,
+    neighbourhood: Neighbourhood,
+) -> Option<Move> {
+    let mut best_neighbour: Option<(Move, f64)> = None;
+    for flip in s.moves(neighbourhood) {
+        let delta = s.move_delta(&flip).0;
+
+        // troca envolve um ítem banido e não satisfaz a aspiração, olhar o próximo. Como é um
+        // problema de max, os valores têm o sinal trocado.
+        if flip.flips().any(|i| tabu.is_tabu(i))
+            && !(tabu.aspires(-(s.value().0 + delta), -s.value().0, -s_best.value().0)
+                && s.move_is_feasible(&flip))
+        {
             continue;
         }
 
+        // movimentos que não melhoram são penalizados pela frequência dos ítens
+        let delta = if delta > 0.0 {
+            delta
+        } else {
+            delta - flip.flips().map(|i| tabu.penalty(i)).sum::<f64>()
+        };
         match best_neighbour {
-            // existe um melhor, descobre se o vizinho atual é melhor
-            Some(ref best) => {
-                if s_prime > best.1 {
-                    best_neighbour = Some((flip, s_prime));
-                }
-            }
-            // melhor não escolhido ainda, aceita o primeiro
-            None => best_neighbour = Some((flip, s_prime)),
+            // existe um melhor e o vizinho atual não é melhor que ele
+            Some((_, best)) if delta <= best => (),
+            // vizinho atual é melhor, ou melhor não escolhido ainda
+            _ => best_neighbour = Some((flip, delta)),
         }
     }
-    best_neighbour
+    best_neighbour.map(|(flip, _)| flip)
 }
 
-fn run(knapsack: &[Item], params: WithPenalty, pparams: PParams) -> (Duration, UWeight) {
+/// Executa a busca tabu, devolvendo também a fração das iterações em que a solução corrente era
+/// inviável.
+fn run<S: KnapsackSolution>(
+    s0: &S,
+    pparams: &PParams,
+    tabu_params: TabuParams,
+) -> Result<(Duration, UWeight, f64), String> {
     let PParams {
         i_max,
-        tabu_memory,
         idle_max,
+        neighbourhood,
+        decode,
         ..
-    } = pparams;
-
-    let max_weight = params.max_weight;
+    } = *pparams;
 
-    // Solução inicial consiste em uma busca gulosa;
-    let mut s = Solution::greedy(knapsack, params);
+    let mut s = s0.clone();
 
-    let mut s_best = s.clone();
+    // a gulosa pode ser inviável com penalidades fracas; a melhor começa viável
+    let mut s_best = repair::decode(&s);
 
-    let mut tabu: TabuList = TabuList::new();
+    let mut tabu = TabuMemory::new(s.items().len(), tabu_params)?;
+    let mut rand = rand::thread_rng();
 
     let now = Instant::now();
     let mut idle = 0;
+    let mut infeasible = 0;
+    let mut iterations = 0;
     for i in 0.. {
-        let (flip_prime, s_prime) =
-            next_neighbour(&s, &tabu, &s_best, max_weight).unwrap_or_else(|| {
-                // todos os movimentos estavam banidos, pega o tabu há mais tempo
-                let (&flip, _) = tabu
-                    .iter()
-                    .min_by_key(|(_, tabu_turns)| **tabu_turns)
-                    .unwrap();
-                (flip, s.flip(flip))
-            });
-
-        // houve melhora
-        if s_prime > s_best && s_prime.total_weight() <= max_weight {
-            s_best = s_prime.clone();
+        let flip_prime = next_neighbour(&s, &tabu, &s_best, neighbourhood).unwrap_or_else(|| {
+            // todos os movimentos estavam banidos, pega o que sai da lista primeiro
+            let flip = (0..s.items().len())
+                .min_by_key(|&i| tabu.expires(i))
+                .unwrap();
+            Move::Flip(flip)
+        });
+        // continua a busca a partir da solução encontrada
+        s.apply(&flip_prime);
+        iterations += 1;
+        let mut improved = false;
+        if !s.is_feasible() {
+            infeasible += 1;
+            if decode {
+                let decoded = repair::decode(&s);
+                if decoded > s_best {
+                    s_best = decoded;
+                    improved = true;
+                }
+            }
+        }
+        s.adapt_penalty();
+
+        if s > s_best && s.is_feasible() {
+            s_best = s.clone();
+            improved = true;
+        }
+        // uma única decisão por iteração: zera se houve melhora, conta se não houve
+        if improved {
             idle = 0;
-        // não houve
         } else {
             idle += 1;
         }
@@ -98,30 +128,27 @@ fn run(knapsack: &[Item], params: WithPenalty, pparams: PParams) -> (Duration, U
             break;
         }
 
-        tabu.retain(|_move, tabu_turns| {
-            *tabu_turns -= 1;
-            // remove trocas cujo tempo de vida se tornou 0
-            *tabu_turns != 0
-        });
-        // println!("{flip_prime} [{}]", s_prime.total_value());
-        tabu.insert(flip_prime, tabu_memory);
-        // continua a busca a partir da solução encontrada
-        s = s_prime;
+        // println!("{flip_prime:?} [{}]", s.total_value());
+        tabu.forbid(flip_prime.flips(), &mut rand);
+        tabu.next_iteration(tabu::hash(s.items()));
     }
     let runtime = now.elapsed();
 
-    (runtime, s_best.total_value())
+    Ok((
+        runtime,
+        s_best.total_value(),
+        infeasible as f64 / iterations as f64,
+    ))
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let (maxw, items) = read_knapsack(&mut open_file())?;
-    let items = &*items;
-
     let pparams = PParams {
         i_max: 5000,
-        tabu_memory: 50,
         idle_max: 0,
         penalty: 3.into(),
+        neighbourhood: flag_value("--neighbourhood")
+            .map_or(Ok(Neighbourhood::Flip), |n| n.parse())?,
+        decode: has_flag("--decode"),
     };
 
     // let pparams = PParams {
@@ -131,14 +158,65 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     //     penalty: 1,
     // };
 
-    let params = WithPenalty {
-        max_weight: maxw,
-        penalty: pparams.penalty,
+    let tabu_params = TabuParams {
+        tenure: flag_value("--tenure").map_or(Ok(Tenure::Fixed), |t| t.parse())?,
+        tabu_memory: 50,
+        tenure_min: 25,
+        tenure_max: 75,
+        diversification: if has_flag("--diversify") { 100.0 } else { 0.0 },
+        aspiration: flag_value("--aspiration").map_or(Ok(Aspiration::Best), |a| a.parse())?,
     };
-    println!("{}", debug_to_kw(&pparams));
 
-    println!("runtime;value");
-    let (runtime, objective_func) = run(items, params, pparams);
-    println!("{:?};{}", runtime.as_secs_f64(), objective_func);
+    println!("{};{}", debug_to_kw(&pparams), debug_to_kw(&tabu_params));
+    println!("runtime;value;infeasible");
+
+    // Instâncias multidimensionais no formato da OR-Library
+    let (runtime, objective_func, infeasible) = if has_flag("--mknap") {
+        let problem = flag_value("--problem").map_or(Ok(0), |p| p.parse())?;
+        let instance = &read_mknap(&mut open_file())?[problem];
+        let evaluation = if has_flag("--repair") {
+            multidim::Evaluation::SurrogateRepair
+        } else {
+            multidim::Evaluation::Penalty(pparams.penalty)
+        };
+        // Solução inicial consiste em uma busca gulosa;
+        let s = multidim::Solution::greedy(instance, evaluation);
+        run(&s, &pparams, tabu_params)?
+    } else if has_flag("--qkp") {
+        let instance = read_qkp(&mut open_file())?;
+        let params = WithPenalty {
+            max_weight: instance.capacity,
+            penalty: pparams.penalty,
+        };
+        // Solução inicial consiste em uma busca gulosa;
+        let s = quadratic::Solution::greedy(&instance, params);
+        run(&s, &pparams, tabu_params)?
+    } else {
+        let (maxw, items) = read_knapsack(&mut open_file())?;
+        // Solução inicial consiste em uma busca gulosa;
+        match flag_value("--penalty").as_deref() {
+            Some("adaptive") => {
+                let params = AdaptivePenalty::new(maxw, pparams.penalty);
+                run(&Solution::greedy(&items, params), &pparams, tabu_params)?
+            }
+            Some("oscillation") => {
+                let params = StrategicOscillation::new(maxw, pparams.penalty);
+                run(&Solution::greedy(&items, params), &pparams, tabu_params)?
+            }
+            _ => {
+                let params = WithPenalty {
+                    max_weight: maxw,
+                    penalty: pparams.penalty,
+                };
+                run(&Solution::greedy(&items, params), &pparams, tabu_params)?
+            }
+        }
+    };
+    println!(
+        "{:?};{};{}",
+        runtime.as_secs_f64(),
+        objective_func,
+        infeasible
+    );
     Ok(())
 }
@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+use exs::knapsack::multidim::{self, read_mknap};
+use exs::knapsack::quadratic::{self, read_qkp};
+use exs::knapsack::{
+    read_knapsack, KnapsackSolution, Neighbourhood, Solution, UWeight, WithPenalty,
+};
+use exs::vns::{Variant, Vns};
+use exs::{debug_to_kw, flag_value, has_flag, open_file};
+
+#[derive(Debug, Clone, Copy)]
+pub struct PParams {
+    pub penalty: UWeight,
+}
+
+fn run<S: KnapsackSolution>(s0: &S, vns: &Vns<Neighbourhood>) -> (Duration, UWeight) {
+    let mut rand = rand::thread_rng();
+
+    let now = Instant::now();
+    let s_best = vns.run(s0, &mut rand);
+    let runtime = now.elapsed();
+
+    (runtime, s_best.total_value())
+}
+
+fn run_all<S: KnapsackSolution>(s0: &S, vns: &Vns<Neighbourhood>, pparams: PParams) {
+    println!("{};{}", debug_to_kw(vns), debug_to_kw(&pparams));
+    println!("runtime;value");
+    for _ in 0..10 {
+        let (runtime, objective_func) = run(s0, vns);
+        println!("{:?};{}", runtime.as_secs_f64(), objective_func);
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let vns = Vns {
+        i_max: 200,
+        variant: flag_value("--variant").map_or(Ok(Variant::General), |v| v.parse())?,
+        descent: Neighbourhood::Flip,
+        skew: 1.0,
+    };
+    let pparams = PParams { penalty: 2.into() };
+
+    // Instâncias multidimensionais no formato da OR-Library
+    if has_flag("--mknap") {
+        let problem = flag_value("--problem").map_or(Ok(0), |p| p.parse())?;
+        let instance = &read_mknap(&mut open_file())?[problem];
+        let evaluation = if has_flag("--repair") {
+            multidim::Evaluation::SurrogateRepair
+        } else {
+            multidim::Evaluation::Penalty(pparams.penalty)
+        };
+        // Solução inicial é gulosa.
+        run_all(
+            &multidim::Solution::greedy(instance, evaluation),
+            &vns,
+            pparams,
+        );
+        return Ok(());
+    }
+
+    // Instâncias da mochila quadrática
+    if has_flag("--qkp") {
+        let instance = read_qkp(&mut open_file())?;
+        let params = WithPenalty {
+            max_weight: instance.capacity,
+            penalty: pparams.penalty,
+        };
+        // Solução inicial é gulosa.
+        run_all(
+            &quadratic::Solution::greedy(&instance, params),
+            &vns,
+            pparams,
+        );
+        return Ok(());
+    }
+
+    let (maxw, items) = read_knapsack(&mut open_file())?;
+    let params = WithPenalty {
+        max_weight: maxw,
+        penalty: pparams.penalty,
+    };
+    // Solução inicial é gulosa.
+    run_all(&Solution::greedy(&items, params), &vns, pparams);
+    Ok(())
+}
@@ -1,11 +1,8 @@
 use exs::tsp::Solution;
-use exs::{debug_to_kw, open_file, Edge, Graph, GraphMat, Node, Weight};
-use itertools::Itertools;
+use exs::{open_file, Edge, Graph, GraphMat, GraphMut, Node, Weight};
 use rand::prelude::Distribution;
-use rand::seq::SliceRandom;
 use rand::Rng;
-use std::f64::consts::E;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct Params {
@@ -36,7 +33,7 @@ fn remove_random(
 
 fn ant_path<'g>(g: &'g dyn Graph, pheromones: &dyn Graph, params: &Params) -> Solution<'g> {
     let mut rand = rand::thread_rng();
-    let mut s = (0..g.node_count() as Node).into_iter().collect::<Vec<_>>();
+    let mut s = (0..g.node_count() as Node).collect::<Vec<_>>();
     let mut path = Vec::<Node>::new();
 
     // Escolhe cidade inicial aleatoriamente
@@ -58,28 +55,24 @@ fn run(g: &dyn Graph, params: &Params) -> (Duration, Weight) {
         pheromones.add_edge(u, v, 1.into());
     }
 
-    let mut pheromones: &mut dyn Graph = &mut pheromones;
+    let pheromones: &mut dyn GraphMut = &mut pheromones;
 
     for _ in 0..params.i_max {
         // Escolhe melhor formiga
         let best = (0..params.ant_count)
-            .into_iter()
             .map(|_| ant_path(g, pheromones, params))
             .max_by(|s1, s2| s1.value.total_cmp(&s2.value))
             .unwrap();
 
         // Evaporação
         for Edge(u, v, w) in pheromones.edges() {
-            let new_weight = (Weight::from(1.0 - params.evap) * w).into();
+            let new_weight = Weight::from(1.0 - params.evap) * w;
             pheromones[(u, v)] = new_weight;
         }
 
         // Atualização das trilhas
         let n = best.nodes.len() as Node;
-        let components = (0..n - 1)
-            .into_iter()
-            .map(|i| (i, i + 1))
-            .chain(Some((n - 1, 0)));
+        let components = (0..n - 1).map(|i| (i, i + 1)).chain(Some((n - 1, 0)));
         for (i, j) in components {
             pheromones[(i, j)] += Weight::from(params.reinforcement) / (best.value);
         }
@@ -0,0 +1,126 @@
+use exs::alns::{AdaptiveWeights, Outcome};
+use exs::tsp::{lns, Solution};
+use exs::{debug_to_kw, open_file, Graph, Weight};
+use rand::Rng;
+use std::f64::consts::E;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct Params {
+    pub i_max: usize,
+    /// Iterações entre atualizações dos pesos dos operadores.
+    pub segment: usize,
+    /// Fração mínima e máxima dos nós removidos a cada iteração.
+    pub q_min: f64,
+    pub q_max: f64,
+    /// Aleatorização das remoções de pior custo e relacionada: quanto maior, mais gulosas.
+    pub p: f64,
+    pub temp0: f64,
+    pub alpha: f64,
+}
+
+const DESTROY: [&str; 3] = ["random", "worst", "shaw"];
+const REPAIR: [&str; 2] = ["greedy", "regret"];
+
+fn run(
+    g: &dyn Graph,
+    params: &Params,
+    destroy: &mut AdaptiveWeights,
+    repair: &mut AdaptiveWeights,
+) -> (Duration, Weight) {
+    let Params {
+        i_max,
+        segment,
+        q_min,
+        q_max,
+        p,
+        temp0: mut temp,
+        alpha,
+    } = *params;
+
+    let mut rand = rand::thread_rng();
+    destroy.reset();
+    repair.reset();
+
+    let n = g.node_count();
+    let q_range = ((q_min * n as f64) as usize).max(1)..=((q_max * n as f64) as usize).max(1);
+
+    let now = Instant::now();
+    // Solução inicial consiste em nós em órdem aleatória, melhorados com 2-opt.
+    let mut s = Solution::random(g);
+    s.two_opt_search();
+    let mut s_best = s.clone();
+    for i in 1..=i_max {
+        let q = rand.gen_range(q_range.clone());
+        let d = destroy.select(&mut rand);
+        let destroyed = match d {
+            0 => lns::random_removal(&s, q, &mut rand),
+            1 => lns::worst_removal(&s, q, p, &mut rand),
+            _ => lns::shaw_removal(&s, q, p, &mut rand),
+        };
+        let r = repair.select(&mut rand);
+        let s_prime = match r {
+            0 => lns::greedy_insertion(g, destroyed, &mut rand),
+            _ => lns::regret_insertion(g, destroyed),
+        };
+
+        let outcome = if s_prime < s_best {
+            Outcome::NewBest
+        } else if s_prime < s {
+            Outcome::Improved
+        } else if rand.gen::<f64>() < E.powf((s.value - s_prime.value).0 / temp) {
+            Outcome::Accepted
+        } else {
+            Outcome::Rejected
+        };
+        destroy.reward(d, outcome);
+        repair.reward(r, outcome);
+
+        if outcome == Outcome::NewBest {
+            s_best = s_prime.clone();
+        }
+        if outcome != Outcome::Rejected {
+            s = s_prime;
+        }
+
+        if i % segment == 0 {
+            destroy.end_segment();
+            repair.end_segment();
+        }
+        temp *= alpha;
+    }
+    let runtime = now.elapsed();
+
+    (runtime, s_best.value)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = open_file();
+    let graph = exs::utils::read_tsp_graph(&mut file)?;
+
+    let params = Params {
+        i_max: 2000,
+        segment: 100,
+        q_min: 0.05,
+        q_max: 0.3,
+        p: 3.0,
+        temp0: 10.0,
+        alpha: 0.998,
+    };
+    if params.p <= 0.0 {
+        return Err("A aleatorização p das remoções deve ser positiva".into());
+    }
+    println!("{}", debug_to_kw(&params));
+
+    let mut destroy = AdaptiveWeights::new(&DESTROY);
+    let mut repair = AdaptiveWeights::new(&REPAIR);
+    println!("runtime;cost");
+    for _ in 0..10 {
+        let (runtime, objective_func) = run(&graph, &params, &mut destroy, &mut repair);
+        println!("{:?};{}", runtime.as_secs_f64(), objective_func);
+    }
+    // estatísticas acumuladas em todas as execuções, com os pesos da última
+    destroy.print_stats();
+    repair.print_stats();
+    Ok(())
+}
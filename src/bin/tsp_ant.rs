@@ -1,10 +1,7 @@
 use exs::tsp::Solution;
-use exs::{debug_to_kw, open_file, Edge, Graph, GraphMat, Node, Weight};
-use itertools::Itertools;
+use exs::{open_file, Edge, Graph, GraphMat, GraphMut, Node, Weight};
 use rand::prelude::Distribution;
-use rand::seq::SliceRandom;
 use rand::Rng;
-use std::f64::consts::E;
 use std::time::{Duration, Instant};
 
 #[derive(Debug)]
@@ -26,9 +23,10 @@ fn remove_random(
     pheromones: &dyn Graph,
     params: &Params,
 ) -> Node {
-    let distributions = s
-        .iter()
-        .map(|k| (pheromones[(i, *k)]).powf(params.alpha) * (g[(i, *k)].recip()).powf(params.beta));
+    let distributions = s.iter().map(|k| {
+        let distance = g.get_edge_weight(i, *k).unwrap();
+        (pheromones[(i, *k)]).powf(params.alpha) * distance.recip().powf(params.beta)
+    });
     let k_index = rand::distributions::WeightedIndex::new(distributions)
         .unwrap()
         .sample(rand);
@@ -37,7 +35,7 @@ fn remove_random(
 
 fn ant_path<'g>(g: &'g dyn Graph, pheromones: &dyn Graph, params: &Params) -> Solution<'g> {
     let mut rand = rand::thread_rng();
-    let mut s = (0..g.node_count() as Node).into_iter().collect::<Vec<_>>();
+    let mut s = (0..g.node_count() as Node).collect::<Vec<_>>();
     let mut path = Vec::<Node>::new();
 
     // Escolhe cidade inicial aleatoriamente
@@ -54,36 +52,31 @@ fn ant_path<'g>(g: &'g dyn Graph, pheromones: &dyn Graph, params: &Params) -> So
 fn run(g: &dyn Graph, params: &Params) -> (Duration, Weight) {
     let mut pheromones = GraphMat::default();
     pheromones.add_nodes(g.node_count());
-    for Edge(u, v, _) in g.edges() {
+    for Edge(u, v, _) in g.unique_edges() {
         // feromonio inicial
         pheromones.add_edge(u, v, params.tau0.into());
     }
 
-    let pheromones: &mut dyn Graph = &mut pheromones;
+    let pheromones: &mut dyn GraphMut = &mut pheromones;
 
     let now = Instant::now();
     let mut most_best = Solution::sequential(g);
     for _ in 0..params.i_max {
         // Escolhe melhor formiga
         let best = (0..params.ant_count)
-            .into_iter()
             .map(|_| ant_path(g, pheromones, params))
             .min()
             // .min_by(|s1, s2| s1.value.total_cmp(&s2.value))
             .unwrap();
 
         // Evaporação
-        for Edge(u, v, w) in pheromones.edges() {
-            let new_weight = Weight::from(1.0 - params.evap) * w;
-            pheromones[(u, v)] = new_weight;
+        for (_, _, w) in pheromones.weights_mut() {
+            *w *= 1.0 - params.evap;
         }
 
         // Reforço da melhor trilha
         let n = best.nodes.len() as Node;
-        let components = (0..n - 1)
-            .into_iter()
-            .map(|i| (i, i + 1))
-            .chain(Some((n - 1, 0)));
+        let components = (0..n - 1).map(|i| (i, i + 1)).chain(Some((n - 1, 0)));
 
         for (i, j) in components {
             pheromones[(i, j)] += Weight::from(params.reinforcement) / best.value;
@@ -98,8 +91,7 @@ fn run(g: &dyn Graph, params: &Params) -> (Duration, Weight) {
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut file = open_file();
-    let mut graph = GraphMat::default();
-    exs::utils::fill_tsp_graph(&mut file, &mut graph)?;
+    let graph = exs::utils::read_tsp_graph(&mut file)?;
 
     let params = Params {
         i_max: 100,
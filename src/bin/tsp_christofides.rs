@@ -0,0 +1,25 @@
+use exs::tsp::christofides::{christofides, Matching};
+use exs::{open_file, Graph, Weight};
+use std::time::{Duration, Instant};
+
+fn run(g: &dyn Graph, matching: Matching) -> Result<(Duration, Weight), String> {
+    let now = Instant::now();
+    let s = christofides(g, matching)?;
+    Ok((now.elapsed(), s.value))
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = open_file();
+    let graph = exs::utils::read_tsp_graph(&mut file)?;
+
+    println!("matching;runtime;cost");
+    for matching in [Matching::Exact, Matching::Greedy] {
+        let (runtime, objective_func) = run(&graph, matching)?;
+        println!(
+            "{matching:?};{:?};{}",
+            runtime.as_secs_f64(),
+            objective_func
+        );
+    }
+    Ok(())
+}
@@ -1,9 +1,9 @@
+use exs::grasp::ReactiveAlpha;
+use exs::relinking::{path_relinking, Direction, ElitePool};
 use exs::tsp::Solution;
-use exs::{debug_to_kw, open_file, Graph, GraphMat, Node, Weight};
+use exs::{debug_to_kw, flag_value, has_flag, open_file, Graph, Node, Weight};
 use itertools::Itertools;
 use rand::seq::SliceRandom;
-use rand::Rng;
-use std::f64::consts::E;
 use std::time::{Duration, Instant};
 
 #[derive(Debug)]
@@ -11,18 +11,28 @@ pub struct Params {
     pub i_max: usize,
     pub idle_max: usize,
     pub a: f64,
+    /// Sentido do religamento de caminhos com as soluções de elite, se houver.
+    pub relinking: Option<Direction>,
+    pub elite_size: usize,
+    pub elite_distance: usize,
+    /// Se `a` é sorteado de `ALPHAS` com probabilidades reajustadas a cada `reactive_period`
+    /// iterações, em vez de fixo. As probabilidades são mantidas entre as execuções.
+    pub reactive: bool,
+    pub reactive_period: usize,
 }
 
+/// Valores de `a` experimentados pelo GRASP reativo. Como o corte é `melhor + a · (pior - melhor)`,
+/// o guloso puro (`a = 0`) fica de fora, pois sempre constrói a mesma solução, assim como o
+/// aleatório puro (`a = 1`).
+const ALPHAS: [f64; 10] = [0.05, 0.1, 0.15, 0.2, 0.3, 0.4, 0.5, 0.6, 0.8, 0.9];
+
 fn eval_candidate(g: &dyn Graph, last_node: Node, candidate: Node) -> Weight {
     g.get_edge_weight(last_node, candidate).unwrap()
 }
 
 fn random_greedy_solution<'g>(g: &'g dyn Graph, a: f64, rand: &mut impl rand::Rng) -> Solution<'g> {
     let mut s = Vec::<Node>::new();
-    let mut lc = (0..g.node_count())
-        .into_iter()
-        .map(|n| n as Node)
-        .collect_vec();
+    let mut lc = (0..g.node_count()).map(|n| n as Node).collect_vec();
     s.push(lc.remove(0));
     while !lc.is_empty() {
         let last_node = *s.last().unwrap();
@@ -35,9 +45,9 @@ fn random_greedy_solution<'g>(g: &'g dyn Graph, a: f64, rand: &mut impl rand::Rn
             .collect_vec();
         // ordena por valor de g
         lrc.sort_unstable_by_key(|k| k.1);
-        let worst = lrc[0].1;
-        let best = lrc[lrc.len() - 1].1;
-        let cutoff = Weight::from(a) * (best - worst) + worst;
+        let best = lrc[0].1;
+        let worst = lrc[lrc.len() - 1].1;
+        let cutoff = Weight::from(a) * (worst - best) + best;
 
         // Remove candidatos cujo g não atende os parametros
         // nesse caso é <= pois é um problema de min
@@ -84,10 +94,24 @@ fn greedy_search(s: Solution) -> Solution {
     s_best
 }
 
-fn run(g: &dyn Graph, params: &Params) -> (Duration, Weight) {
-    let Params { i_max, a, idle_max } = *params;
+fn run(
+    g: &dyn Graph,
+    params: &Params,
+    mut reactive: Option<&mut ReactiveAlpha>,
+) -> (Duration, Weight) {
+    let Params {
+        i_max,
+        a,
+        idle_max,
+        relinking,
+        elite_size,
+        elite_distance,
+        reactive_period,
+        ..
+    } = *params;
 
     let mut s_best = None;
+    let mut elite = ElitePool::new(elite_size, elite_distance);
 
     let mut rand = rand::thread_rng();
 
@@ -95,8 +119,36 @@ fn run(g: &dyn Graph, params: &Params) -> (Duration, Weight) {
 
     let now = Instant::now();
     for i in 0.. {
+        // no modo reativo, sorteia o `a` desta iteração
+        let (alpha, a) = match reactive.as_deref() {
+            Some(reactive) => {
+                let (index, alpha) = reactive.select(&mut rand);
+                (Some(index), alpha)
+            }
+            None => (None, a),
+        };
         let s = random_greedy_solution(g, a, &mut rand);
-        let s = greedy_search(s);
+        let mut s = greedy_search(s);
+
+        if let Some(direction) = relinking {
+            // religa o ótimo local com uma solução de elite e busca a partir do melhor ponto
+            // do caminho
+            let guide = elite.choose(&mut rand).cloned();
+            let relinked = guide
+                .and_then(|guide| path_relinking(&s, &guide, direction))
+                .map(greedy_search);
+            elite.insert(&s);
+            if let Some(relinked) = relinked {
+                elite.insert(&relinked);
+                if relinked < s {
+                    s = relinked;
+                }
+            }
+        }
+
+        if let (Some(reactive), Some(index)) = (reactive.as_deref_mut(), alpha) {
+            reactive.record(index, s.value.0);
+        }
 
         // Na primeira iteração não há uma solução melhor ainda
         let Some(ref mut s_best) = s_best else {
@@ -113,6 +165,12 @@ fn run(g: &dyn Graph, params: &Params) -> (Duration, Weight) {
             idle += 1;
         }
 
+        if let Some(reactive) = reactive.as_deref_mut() {
+            if (i + 1) % reactive_period == 0 {
+                reactive.update(s_best.value.0);
+            }
+        }
+
         // se idle_max != 0, quer dizer que estamos limitando por iterações sem melhoria
         if idle_max != 0 && idle >= idle_max {
             // quantidade de turnos sem melhora excedeu o parâmetro.
@@ -132,30 +190,46 @@ fn run(g: &dyn Graph, params: &Params) -> (Duration, Weight) {
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut file = open_file();
-    let mut graph = GraphMat::default();
-    exs::utils::fill_tsp_graph(&mut file, &mut graph)?;
+    let graph = exs::utils::read_tsp_graph(&mut file)?;
 
     let alt = false;
+    let relinking = flag_value("--relink").map(|d| d.parse()).transpose()?;
 
     let params = if alt {
         Params {
             i_max: 0,
             idle_max: 300,
             a: 0.15,
+            relinking,
+            elite_size: 10,
+            elite_distance: 5,
+            reactive: has_flag("--reactive"),
+            reactive_period: 20,
         }
     } else {
         Params {
             i_max: 300,
             idle_max: 0,
             a: 0.15,
+            relinking,
+            elite_size: 10,
+            elite_distance: 5,
+            reactive: has_flag("--reactive"),
+            reactive_period: 20,
         }
     };
     println!("{}", debug_to_kw(&params));
 
+    let mut reactive = params.reactive.then(|| ReactiveAlpha::new(&ALPHAS, true));
+
     println!("runtime;cost");
     for _ in 0..10 {
-        let (runtime, objective_func) = run(&graph, &params);
+        let (runtime, objective_func) = run(&graph, &params, reactive.as_mut());
         println!("{:?};{}", runtime.as_secs_f64(), objective_func);
     }
+    // distribuição de `a` acumulada ao longo de todas as execuções
+    if let Some(reactive) = reactive {
+        reactive.print_distribution();
+    }
     Ok(())
 }
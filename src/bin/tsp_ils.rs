@@ -0,0 +1,141 @@
+use exs::tsp::Solution;
+use exs::{debug_to_kw, flag_value, open_file, Graph, Weight};
+use rand::Rng;
+use std::f64::consts::E;
+use std::time::{Duration, Instant};
+
+/// Critério de aceitação do ótimo local obtido após a perturbação.
+#[derive(Debug, Clone, Copy)]
+pub enum Acceptance {
+    /// Só aceita se melhorar a solução corrente.
+    Better,
+    /// Sempre aceita.
+    RandomWalk,
+    /// Só aceita se melhorar, e recomeça de uma solução aleatória após `restart_after`
+    /// iterações sem melhora.
+    Restart,
+    /// Aceita pioras com a probabilidade do SA, com temperatura resfriada geometricamente.
+    Annealing,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Perturbation {
+    DoubleBridge,
+    /// Embaralha um segmento de até `segment_len` nós.
+    Segment,
+}
+
+#[derive(Debug)]
+pub struct Params {
+    pub i_max: usize,
+    pub acceptance: Acceptance,
+    pub perturbation: Perturbation,
+    pub segment_len: usize,
+    pub restart_after: usize,
+    pub temp0: f64,
+    pub alpha: f64,
+}
+
+fn local_optimum(g: &dyn Graph) -> Solution<'_> {
+    let mut s = Solution::random(g);
+    s.local_search();
+    s
+}
+
+fn run(g: &dyn Graph, params: &Params) -> (Duration, Weight) {
+    let Params {
+        i_max,
+        acceptance,
+        perturbation,
+        segment_len,
+        restart_after,
+        temp0: mut temp,
+        alpha,
+    } = *params;
+
+    let mut rand = rand::thread_rng();
+
+    let now = Instant::now();
+    // Solução inicial é um ótimo local a partir de nós em ordem aleatória.
+    let mut s = local_optimum(g);
+    let mut s_best = s.clone();
+    let mut idle = 0;
+    for _ in 0..i_max {
+        let mut s_prime = match perturbation {
+            Perturbation::DoubleBridge => s.double_bridge(&mut rand),
+            Perturbation::Segment => s.shuffle_segment(segment_len, &mut rand),
+        };
+        s_prime.local_search();
+
+        if s_prime < s_best {
+            s_best = s_prime.clone();
+        }
+
+        match acceptance {
+            Acceptance::Better => {
+                if s_prime < s {
+                    s = s_prime;
+                }
+            }
+            Acceptance::RandomWalk => s = s_prime,
+            Acceptance::Restart => {
+                if s_prime < s {
+                    s = s_prime;
+                    idle = 0;
+                } else {
+                    idle += 1;
+                }
+                // estagnou, recomeça de outro ótimo local
+                if idle >= restart_after {
+                    s = local_optimum(g);
+                    idle = 0;
+                }
+            }
+            Acceptance::Annealing => {
+                if s_prime < s || rand.gen::<f64>() < E.powf((s.value - s_prime.value).0 / temp) {
+                    s = s_prime;
+                }
+                temp *= alpha;
+            }
+        }
+    }
+    let runtime = now.elapsed();
+
+    (runtime, s_best.value)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = open_file();
+    let graph = exs::utils::read_tsp_graph(&mut file)?;
+
+    let acceptance = match flag_value("--acceptance").as_deref() {
+        None | Some("better") => Acceptance::Better,
+        Some("walk") => Acceptance::RandomWalk,
+        Some("restart") => Acceptance::Restart,
+        Some("sa") => Acceptance::Annealing,
+        Some(other) => return Err(format!("Critério de aceitação desconhecido: {other}").into()),
+    };
+    let perturbation = match flag_value("--perturbation").as_deref() {
+        None | Some("bridge") => Perturbation::DoubleBridge,
+        Some("segment") => Perturbation::Segment,
+        Some(other) => return Err(format!("Perturbação desconhecida: {other}").into()),
+    };
+
+    let params = Params {
+        i_max: 200,
+        acceptance,
+        perturbation,
+        segment_len: 8,
+        restart_after: 50,
+        temp0: 10.0,
+        alpha: 0.98,
+    };
+    println!("{}", debug_to_kw(&params));
+
+    println!("runtime;cost");
+    for _ in 0..10 {
+        let (runtime, objective_func) = run(&graph, &params);
+        println!("{:?};{}", runtime.as_secs_f64(), objective_func);
+    }
+    Ok(())
+}
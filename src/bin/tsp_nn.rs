@@ -0,0 +1,28 @@
+use exs::tsp::{CandidateList, Solution};
+use exs::{debug_to_kw, open_file, CoordGraph, Weight};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct Params {
+    pub candidates: usize,
+}
+
+fn run(g: &CoordGraph, params: &Params) -> (Duration, Weight) {
+    let now = Instant::now();
+    let candidates = CandidateList::from_coords(g.coords(), g.metric(), params.candidates);
+    let s = Solution::nearest_neighbour(g, &candidates);
+    (now.elapsed(), s.value)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // O grafo implícito permite ler instâncias com dezenas de milhares de cidades.
+    let graph = CoordGraph::read(&mut open_file())?;
+
+    let params = Params { candidates: 10 };
+    println!("{}", debug_to_kw(&params));
+
+    println!("runtime;cost");
+    let (runtime, objective_func) = run(&graph, &params);
+    println!("{:?};{}", runtime.as_secs_f64(), objective_func);
+    Ok(())
+}
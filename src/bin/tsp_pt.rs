@@ -0,0 +1,61 @@
+use exs::tempering::{geometric_ladder, Tempering};
+use exs::tsp::{Neighbourhood, Solution};
+use exs::{debug_to_kw, flag_value, open_file, Graph, Weight};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct Params {
+    /// Quantidade de réplicas, uma por thread.
+    pub replicas: usize,
+    pub temp_min: f64,
+    pub temp_max: f64,
+    pub rounds: usize,
+    /// Passos de Metropolis de cada réplica entre duas rodadas de trocas.
+    pub sweep: usize,
+    pub neighbourhood: Neighbourhood,
+}
+
+fn run(g: &dyn Graph, tempering: &mut Tempering, params: &Params) -> (Duration, Weight) {
+    // Solução inicial consiste em nós em órdem aleatória.
+    let s = Solution::random(g);
+
+    let now = Instant::now();
+    let s_best = tempering.run(&s, params.neighbourhood).unwrap();
+    let runtime = now.elapsed();
+
+    (runtime, s_best.value)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = open_file();
+    let graph = exs::utils::read_tsp_graph(&mut file)?;
+
+    // por padrão, uma réplica por núcleo, mas ao menos 4 para que haja trocas
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get().max(4));
+    let replicas = flag_value("--replicas").map_or(Ok(threads), |r| r.parse())?;
+    if replicas == 0 {
+        return Err("É preciso ao menos uma réplica".into());
+    }
+    let params = Params {
+        replicas,
+        temp_min: 0.5,
+        temp_max: 50.0,
+        rounds: 500,
+        sweep: 200,
+        neighbourhood: flag_value("--neighbourhood")
+            .map_or(Ok(Neighbourhood::TwoOpt), |n| n.parse())?,
+    };
+    println!("{}", debug_to_kw(&params));
+
+    let temps = geometric_ladder(params.temp_min, params.temp_max, params.replicas);
+    let mut tempering = Tempering::new(temps, params.rounds, params.sweep);
+
+    println!("runtime;cost");
+    for _ in 0..10 {
+        let (runtime, objective_func) = run(&graph, &mut tempering, &params);
+        println!("{:?};{}", runtime.as_secs_f64(), objective_func);
+    }
+    // taxas de troca acumuladas entre as execuções
+    tempering.print_stats();
+    Ok(())
+}
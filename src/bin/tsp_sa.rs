@@ -1,25 +1,22 @@
+use exs::annealing::{initial_temperature, Annealing, Cooling, Schedule};
 use exs::tsp::Solution;
-use exs::{debug_to_kw, open_file, Graph, GraphMat, Weight};
-use rand::Rng;
-use std::f64::consts::E;
+use exs::{debug_to_kw, flag_value, has_flag, open_file, Graph, Weight};
 use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub struct Params {
-    pub i_max: usize,
-    pub epsilon: f64,
-    pub alpha: f64,
     pub temp0: f64,
-    pub exponential_cooling: bool,
+    /// Se diferente de 0, `temp0` é calculada para aceitar essa fração das pioras amostradas na
+    /// solução inicial.
+    pub initial_acceptance: f64,
+    pub samples: usize,
 }
 
-fn run(g: &dyn Graph, params: &Params) -> (Duration, Weight) {
+fn run(g: &dyn Graph, schedule: Schedule, params: &Params) -> Result<(Duration, Weight), String> {
     let Params {
-        i_max,
-        epsilon,
-        alpha,
-        temp0: mut temp,
-        exponential_cooling,
+        temp0,
+        initial_acceptance,
+        samples,
     } = *params;
 
     // Solução inicial consiste em nós em órdem aleatória.
@@ -30,47 +27,81 @@ fn run(g: &dyn Graph, params: &Params) -> (Duration, Weight) {
     let mut rand = rand::thread_rng();
 
     let now = Instant::now();
-    while temp > epsilon {
-        for _ in 0..i_max {
+    let temp0 = if initial_acceptance != 0.0 {
+        // amostra vizinhos da solução inicial
+        let worsenings = (0..samples).map(|_| (s.random_neighbour(&mut rand).value - s.value).0);
+        initial_temperature(worsenings, initial_acceptance)?.unwrap_or(temp0)
+    } else {
+        temp0
+    };
+    let mut annealing = Annealing::new(schedule, temp0);
+
+    while !annealing.is_frozen() {
+        while !annealing.stage_done() {
             let s_prime = s.random_neighbour(&mut rand);
 
-            if s_prime < s {
+            if annealing.accept((s_prime.value - s.value).0, &mut rand) {
                 s = s_prime;
                 if s < s_best {
                     s_best = s.clone();
+                    annealing.improved();
                 }
-            } else if rand.gen::<f64>() < E.powf((s.value - s_prime.value).0 / temp) {
-                s = s_prime;
             }
         }
-        if exponential_cooling {
-            temp *= alpha;
-        } else {
-            temp -= alpha;
-        }
+        annealing.next_stage();
     }
     let runtime = now.elapsed();
 
-    (runtime, s_best.value)
+    Ok((runtime, s_best.value))
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut file = open_file();
-    let mut graph = GraphMat::default();
-    exs::utils::fill_tsp_graph(&mut file, &mut graph)?;
+    let graph = exs::utils::read_tsp_graph(&mut file)?;
 
-    let params = Params {
+    let cooling = flag_value("--cooling").map_or(Ok(Cooling::Linear), |c| c.parse())?;
+    let alpha = match flag_value("--alpha") {
+        Some(alpha) => alpha.parse()?,
+        None => match cooling {
+            Cooling::Linear => 0.9,
+            Cooling::Geometric => 0.95,
+            Cooling::Logarithmic => 0.0,
+            Cooling::LundyMees => 0.1,
+            Cooling::Adaptive => 0.7,
+        },
+    };
+    let equilibrium = has_flag("--equilibrium");
+    let reheat = has_flag("--reheat");
+
+    let schedule = Schedule {
+        cooling,
+        alpha,
         epsilon: 0.005,
-        i_max: 10,
+        // no equilíbrio, estágios longos que terminam cedo em temperaturas altas
+        i_max: if equilibrium { 1000 } else { 10 },
+        accepted_max: if equilibrium { 100 } else { 0 },
+        stages_max: flag_value("--stages").map_or(
+            Ok(if cooling == Cooling::Logarithmic {
+                1000
+            } else {
+                0
+            }),
+            |s| s.parse(),
+        )?,
+        reheat_after: if reheat { 20 } else { 0 },
+        reheat_factor: 10.0,
+        reheats_max: 5,
+    };
+    let params = Params {
         temp0: 10.0,
-        alpha: 0.9,
-        exponential_cooling: false,
+        initial_acceptance: if has_flag("--auto-temp") { 0.8 } else { 0.0 },
+        samples: 100,
     };
-    println!("{}", debug_to_kw(&params));
+    println!("{};{}", debug_to_kw(&schedule), debug_to_kw(&params));
 
     println!("runtime;cost");
     for _ in 0..10 {
-        let (runtime, objective_func) = run(&graph, &params);
+        let (runtime, objective_func) = run(&graph, schedule, &params)?;
         println!("{:?};{}", runtime.as_secs_f64(), objective_func);
     }
     Ok(())
@@ -1,107 +1,84 @@
+use exs::tabu::{self, Aspiration, TabuMemory, TabuParams, Tenure};
 use exs::tsp::Solution;
-use exs::{debug_to_kw, open_file, Graph, GraphMat, Node, Weight};
-use itertools::Itertools;
-use std::collections::BTreeMap;
+use exs::{debug_to_kw, flag_value, has_flag, open_file, Graph, Weight};
 use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub struct Params {
     pub i_max: usize,
-    pub tabu_memory: usize,
     pub idle_max: usize,
 }
 
-type TabuList = BTreeMap<(Node, Node), usize>;
-
-fn tabu_get(tabu: &TabuList, mut a: Node, mut b: Node) -> Option<usize> {
-    // garante ordenação entre A e B, tal que
-    // tabu_get(tabu, a, b) == tabu_get(tabu, b, a)
-    if b > a {
-        std::mem::swap(&mut a, &mut b);
-    }
-    tabu.get(&(a, b)).copied()
-}
-
-fn tabu_set(tabu: &mut TabuList, mut a: Node, mut b: Node, val: usize) {
-    // garante ordenação entre A e B, tal que
-    // tabu_set(tabu, a, b, val) == tabu_set(tabu, b, a, val)
-    if b > a {
-        std::mem::swap(&mut a, &mut b);
-    }
-    tabu.insert((a, b), val);
+/// Atributo da troca das posições `a` e `b`: o par, sem ordem.
+fn attribute(n: usize, a: usize, b: usize) -> usize {
+    a.min(b) * n + a.max(b)
 }
 
-fn next_neighbour<'g>(
-    s: &Solution<'g>,
-    tabu: &TabuList,
-    s_best: &Solution<'g>,
-) -> Option<((Node, Node), Solution<'g>)> {
-    let all_nodes = 0..(s.nodes.len() as Node);
-    let mut best_neighbour: Option<((Node, Node), Solution<'g>)> = None;
-    for (a, b) in all_nodes
-        .clone()
-        // itera sobre todos as combinações de N x N nós.
-        .cartesian_product(all_nodes)
-    {
-        // remove não-troca
-        if a == b {
-            continue;
-        }
-
-        let s_prime = s.swap(a as _, b as _);
-        // aspiração: troca é uma melhora absoluta
-        if s_prime < *s_best {
-            return Some(((a, b), s_prime));
-        }
-
-        // troca está banida, olhar o próximo
-        if tabu_get(tabu, a, b).is_some() {
-            continue;
-        }
+fn next_neighbour(s: &Solution, tabu: &TabuMemory, s_best: &Solution) -> Option<(usize, usize)> {
+    let n = s.nodes.len();
+    let mut best_neighbour: Option<((usize, usize), f64)> = None;
+    // itera sobre todos os pares de posições
+    for a in 0..n {
+        for b in a + 1..n {
+            let delta = s.swap_delta(a, b).0;
+            let candidate = (s.value + delta).0;
+
+            // troca está banida e não satisfaz a aspiração, olhar o próximo
+            if tabu.is_tabu(attribute(n, a, b))
+                && !tabu.aspires(candidate, s.value.0, s_best.value.0)
+            {
+                continue;
+            }
 
-        match best_neighbour {
-            // existe um melhor, descobre se o vizinho atual é melhor
-            Some(ref best) => {
-                if s_prime < best.1 {
-                    best_neighbour = Some(((a, b), s_prime));
-                }
+            // movimentos que não melhoram são penalizados pela frequência
+            let delta = if delta < 0.0 {
+                delta
+            } else {
+                delta + tabu.penalty(attribute(n, a, b))
+            };
+            match best_neighbour {
+                // existe um melhor e o vizinho atual não é melhor que ele
+                Some((_, best)) if delta >= best => (),
+                // vizinho atual é melhor, ou melhor não escolhido ainda
+                _ => best_neighbour = Some(((a, b), delta)),
             }
-            // melhor não escolhido ainda, aceita o primeiro
-            None => best_neighbour = Some(((a, b), s_prime)),
         }
     }
-    best_neighbour
+    best_neighbour.map(|(swap, _)| swap)
 }
 
-fn run(g: &dyn Graph, params: &Params) -> (Duration, Weight) {
-    let Params {
-        i_max,
-        tabu_memory,
-        idle_max,
-    } = *params;
+fn run(
+    g: &dyn Graph,
+    params: &Params,
+    tabu_params: TabuParams,
+) -> Result<(Duration, Weight), String> {
+    let Params { i_max, idle_max } = *params;
 
     // Solução inicial consiste em nós em órdem sequencial
     let mut s = Solution::sequential(g);
+    let n = s.nodes.len();
 
     let mut s_best = s.clone();
 
-    let mut tabu: TabuList = TabuList::new();
+    let mut tabu = TabuMemory::new(n * n, tabu_params)?;
+    let mut rand = rand::thread_rng();
 
     let now = Instant::now();
     let mut idle = 0;
     for i in 0.. {
-        let (swap_prime, s_prime) = next_neighbour(&s, &tabu, &s_best).unwrap_or_else(|| {
-            // todos os movimentos estavam banidos, pega o tabu há mais tempo
-            let ((a, b), _) = tabu
-                .iter()
-                .min_by_key(|(_move, tabu_turns)| **tabu_turns)
-                .unwrap();
-            ((*a, *b), s.swap(*a as _, *b as _))
+        let (a, b) = next_neighbour(&s, &tabu, &s_best).unwrap_or_else(|| {
+            // todos os movimentos estavam banidos, pega o que sai da lista primeiro
+            (0..n)
+                .flat_map(|a| (a + 1..n).map(move |b| (a, b)))
+                .min_by_key(|&(a, b)| tabu.expires(attribute(n, a, b)))
+                .unwrap()
         });
+        // continua a busca a partir da solução encontrada
+        s = s.swap(a, b);
 
         // houve melhora
-        if s_prime < s_best {
-            s_best = s_prime.clone();
+        if s < s_best {
+            s_best = s.clone();
             idle = 0;
         // não houve
         } else {
@@ -120,42 +97,39 @@ fn run(g: &dyn Graph, params: &Params) -> (Duration, Weight) {
             break;
         }
 
-        tabu.retain(|_move, tabu_turns| {
-            *tabu_turns -= 1;
-            // remove trocas cujo tempo de vida se tornou 0
-            *tabu_turns != 0
-        });
-        let (a, b) = swap_prime;
-        tabu_set(&mut tabu, a, b, tabu_memory);
-        // println!("({a}, {b}) {}", s_prime.value);
-        // continua a busca a partir da solução encontrada
-        s = s_prime;
+        tabu.forbid([attribute(n, a, b)], &mut rand);
+        tabu.next_iteration(tabu::hash(&s.nodes));
     }
     let runtime = now.elapsed();
 
-    (runtime, s_best.value)
+    Ok((runtime, s_best.value))
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut file = open_file();
-    let mut graph = GraphMat::default();
-    exs::utils::fill_tsp_graph(&mut file, &mut graph)?;
+    let graph = exs::utils::read_tsp_graph(&mut file)?;
 
     // let params = Params {
     //     i_max: 5000,
-    //     tabu_memory: 40,
     //     idle_max: 0,
     // };
 
     let params = Params {
         i_max: 0,
-        tabu_memory: 100,
         idle_max: 50,
     };
-    println!("{}", debug_to_kw(&params));
+    let tabu_params = TabuParams {
+        tenure: flag_value("--tenure").map_or(Ok(Tenure::Fixed), |t| t.parse())?,
+        tabu_memory: 100,
+        tenure_min: 50,
+        tenure_max: 150,
+        diversification: if has_flag("--diversify") { 50.0 } else { 0.0 },
+        aspiration: flag_value("--aspiration").map_or(Ok(Aspiration::Best), |a| a.parse())?,
+    };
+    println!("{};{}", debug_to_kw(&params), debug_to_kw(&tabu_params));
 
     println!("runtime;cost");
-    let (runtime, objective_func) = run(&graph, &params);
+    let (runtime, objective_func) = run(&graph, &params, tabu_params)?;
     println!("{:?};{}", runtime.as_secs_f64(), objective_func);
     Ok(())
 }
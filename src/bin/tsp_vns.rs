@@ -0,0 +1,35 @@
+use exs::tsp::{Neighbourhood, Solution};
+use exs::vns::{Variant, Vns};
+use exs::{debug_to_kw, flag_value, open_file, Graph, Weight};
+use std::time::{Duration, Instant};
+
+fn run(g: &dyn Graph, params: &Vns<Neighbourhood>) -> (Duration, Weight) {
+    let mut rand = rand::thread_rng();
+
+    let now = Instant::now();
+    // Solução inicial consiste em nós em órdem aleatória.
+    let s_best = params.run(&Solution::random(g), &mut rand);
+    let runtime = now.elapsed();
+
+    (runtime, s_best.value)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = open_file();
+    let graph = exs::utils::read_tsp_graph(&mut file)?;
+
+    let params = Vns {
+        i_max: 100,
+        variant: flag_value("--variant").map_or(Ok(Variant::General), |v| v.parse())?,
+        descent: Neighbourhood::TwoOpt,
+        skew: 1.0,
+    };
+    println!("{}", debug_to_kw(&params));
+
+    println!("runtime;cost");
+    for _ in 0..10 {
+        let (runtime, objective_func) = run(&graph, &params);
+        println!("{:?};{}", runtime.as_secs_f64(), objective_func);
+    }
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! GRASP reativo: o parâmetro `alpha` da lista restrita de candidatos é sorteado de um conjunto
+//! discreto, com probabilidades reajustadas periodicamente conforme a qualidade média das
+//! soluções obtidas com cada valor.
+use rand::Rng;
+
+#[derive(Debug, Clone)]
+pub struct ReactiveAlpha {
+    alphas: Vec<f64>,
+    probabilities: Vec<f64>,
+    /// Soma das avaliações e quantidade de soluções obtidas com cada `alpha`.
+    sums: Vec<f64>,
+    uses: Vec<usize>,
+    /// Expoente que amplifica as diferenças de qualidade no reajuste.
+    pub amplification: f64,
+    /// Se o problema é de minimização.
+    pub minimize: bool,
+}
+
+impl ReactiveAlpha {
+    pub fn new(alphas: &[f64], minimize: bool) -> Self {
+        let n = alphas.len();
+        Self {
+            alphas: alphas.to_vec(),
+            probabilities: vec![1.0 / n as f64; n],
+            sums: vec![0.0; n],
+            uses: vec![0; n],
+            amplification: 10.0,
+            minimize,
+        }
+    }
+
+    /// Sorteia um `alpha` conforme as probabilidades atuais, devolvendo seu índice e valor.
+    pub fn select(&self, rand: &mut impl Rng) -> (usize, f64) {
+        let mut roulette = rand.gen::<f64>();
+        let chosen = self
+            .probabilities
+            .iter()
+            .position(|p| {
+                roulette -= p;
+                roulette < 0.0
+            })
+            // erros de arredondamento podem deixar a roleta passar do último
+            .unwrap_or(self.alphas.len() - 1);
+        (chosen, self.alphas[chosen])
+    }
+
+    /// Registra a avaliação da solução obtida com o `alpha` de índice `index`.
+    pub fn record(&mut self, index: usize, value: f64) {
+        self.sums[index] += value;
+        self.uses[index] += 1;
+    }
+
+    /// Reajusta as probabilidades: cada `alpha` recebe peso `(melhor / média)^δ` em problemas de
+    /// minimização, ou `(média / melhor)^δ` nos de maximização. Valores ainda não usados recebem
+    /// o peso máximo, 1, para que sejam experimentados.
+    ///
+    /// Se `best` é zero ou não é finito, as razões não fazem sentido e as probabilidades
+    /// anteriores são mantidas.
+    pub fn update(&mut self, best: f64) {
+        if best == 0.0 || !best.is_finite() {
+            return;
+        }
+        let weights = self
+            .sums
+            .iter()
+            .zip(&self.uses)
+            .map(|(sum, uses)| {
+                if *uses == 0 {
+                    return 1.0;
+                }
+                let mean = sum / *uses as f64;
+                let quality = if self.minimize {
+                    best / mean
+                } else {
+                    mean / best
+                };
+                quality.powf(self.amplification)
+            })
+            .collect::<Vec<_>>();
+        let total: f64 = weights.iter().sum();
+        if !total.is_finite() || total <= 0.0 {
+            return;
+        }
+        for (p, w) in self.probabilities.iter_mut().zip(weights) {
+            *p = w / total;
+        }
+    }
+
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+
+    /// Imprime uma linha por `alpha` no formato `alpha;probability;uses;mean`.
+    pub fn print_distribution(&self) {
+        println!("alpha;probability;uses;mean");
+        for (((alpha, p), uses), sum) in self
+            .alphas
+            .iter()
+            .zip(&self.probabilities)
+            .zip(&self.uses)
+            .zip(&self.sums)
+        {
+            let mean = if *uses == 0 { 0.0 } else { sum / *uses as f64 };
+            println!("{alpha};{p};{uses};{mean}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_favours_better_alphas() {
+        let mut reactive = ReactiveAlpha::new(&[0.1, 0.5], false);
+        reactive.record(0, 10.0);
+        reactive.record(1, 5.0);
+        reactive.update(10.0);
+        let p = reactive.probabilities();
+        assert!(p[0] > p[1]);
+        assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn update_ignores_zero_best() {
+        let mut reactive = ReactiveAlpha::new(&[0.1, 0.5], false);
+        reactive.record(0, 0.0);
+        reactive.record(1, 0.0);
+        reactive.update(0.0);
+        assert_eq!(reactive.probabilities(), [0.5, 0.5]);
+        let (index, _) = reactive.select(&mut rand::thread_rng());
+        assert!(index < 2);
+    }
+}
@@ -1,9 +1,253 @@
-use std::{io::BufRead, ops::Sub};
+use std::{io::BufRead, ops::Sub, str::FromStr};
 
 pub use crate::Weight as UWeight;
 pub type Weight = UWeight;
 
 use bitvec::vec::BitVec;
+use rand::{seq::IteratorRandom, Rng};
+
+pub mod bounded;
+pub mod choice;
+pub mod construction;
+pub mod lns;
+pub mod multidim;
+pub mod multiple;
+pub mod quadratic;
+pub mod repair;
+
+/// Movimentos sobre soluções binárias, descritos pelos ítens que invertem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Move {
+    Flip(usize),
+    /// Remove um ítem escolhido e adiciona um que estava fora.
+    Swap {
+        removed: usize,
+        added: usize,
+    },
+    /// Inverte dois ítens quaisquer.
+    TwoFlip(usize, usize),
+    /// Adiciona um ítem e remove, em seguida, os ítens necessários para voltar a caber na
+    /// mochila.
+    EjectionChain {
+        added: usize,
+        removed: Vec<usize>,
+    },
+}
+
+impl Move {
+    /// Ítens invertidos pelo movimento.
+    pub fn flips(&self) -> impl Iterator<Item = usize> + '_ {
+        let (first, second, rest) = match self {
+            Move::Flip(i) => (*i, None, &[][..]),
+            Move::Swap { removed, added } => (*removed, Some(*added), &[][..]),
+            Move::TwoFlip(i, j) => (*i, Some(*j), &[][..]),
+            Move::EjectionChain { added, removed } => (*added, None, &removed[..]),
+        };
+        std::iter::once(first)
+            .chain(second)
+            .chain(rest.iter().copied())
+    }
+}
+
+/// Vizinhanças das soluções binárias.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// Inverte um ítem.
+    #[default]
+    Flip,
+    /// Troca um ítem escolhido por um que estava fora.
+    Swap,
+    /// Inverte dois ítens.
+    TwoFlip,
+    /// Adiciona um ítem e remove os de pior razão valor/peso até que a solução seja viável.
+    EjectionChain,
+}
+
+impl Neighbourhood {
+    /// Vizinhanças da descida em vizinhança variável, em ordem.
+    pub const VND: [Neighbourhood; 3] = [Self::Flip, Self::Swap, Self::TwoFlip];
+}
+
+impl FromStr for Neighbourhood {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flip" => Ok(Self::Flip),
+            "swap" => Ok(Self::Swap),
+            "2flip" => Ok(Self::TwoFlip),
+            "ejection" => Ok(Self::EjectionChain),
+            _ => Err(format!("Vizinhança desconhecida: {s}")),
+        }
+    }
+}
+
+/// Razão `value / weight`. Um ítem sem peso sempre cabe: se o valor não for negativo, sempre
+/// vale a pena levá-lo e a razão é infinita; se for, nunca vale e a razão é menos infinito.
+pub fn value_ratio(value: UWeight, weight: UWeight) -> UWeight {
+    if weight.0 != 0.0 {
+        value / weight
+    } else if value.0 >= 0.0 {
+        f64::INFINITY.into()
+    } else {
+        f64::NEG_INFINITY.into()
+    }
+}
+
+/// Operações comuns às soluções binárias de problemas da mochila, para que as metaheurísticas
+/// possam ser escritas uma única vez para todas as variantes.
+pub trait KnapsackSolution: Clone + PartialOrd {
+    /// Quantidade de ítens da instância.
+    fn item_count(&self) -> usize;
+    /// Ítens escolhidos.
+    fn items(&self) -> &BitVec;
+    /// Avaliação da solução, que pode incluir penalidades.
+    fn value(&self) -> Weight;
+    /// Soma dos valores dos ítens escolhidos.
+    fn total_value(&self) -> UWeight;
+    /// Soma dos pesos dos ítens escolhidos. Em problemas com várias restrições, é o peso
+    /// substituto (a soma dos pesos normalizados pelas capacidades).
+    fn total_weight(&self) -> UWeight;
+    /// Valor do ítem `index`.
+    fn item_value(&self, index: usize) -> UWeight;
+    /// Peso do ítem `index`, na mesma medida de [`KnapsackSolution::total_weight`].
+    fn item_weight(&self, index: usize) -> UWeight;
+    /// Razão valor/peso do ítem `index`, o critério das construções e reparações gulosas. Veja
+    /// [`value_ratio`] para ítens sem peso.
+    fn ratio(&self, index: usize) -> UWeight {
+        value_ratio(self.item_value(index), self.item_weight(index))
+    }
+    /// Indica se a solução respeita todas as restrições de capacidade.
+    fn is_feasible(&self) -> bool;
+    fn flip(&self, index: usize) -> Self;
+    /// Quanto a avaliação mudaria ao inverter o ítem `index`, sem construir a solução vizinha.
+    fn flip_delta(&self, index: usize) -> Weight {
+        self.flip(index).value() - self.value()
+    }
+    /// Indica se a solução continuaria viável após inverter o ítem `index`.
+    fn flip_is_feasible(&self, index: usize) -> bool {
+        self.flip(index).is_feasible()
+    }
+    /// Inverte o ítem `index` na própria solução.
+    fn flip_in_place(&mut self, index: usize) {
+        *self = self.flip(index);
+    }
+    /// Atualiza a penalidade do método de avaliação com a viabilidade da solução, quando ela é
+    /// adaptativa, e reavalia a solução.
+    fn adapt_penalty(&mut self) {}
+    /// Solução vazia sobre a mesma instância e com o mesmo método de avaliação.
+    fn empty(&self) -> Self;
+
+    /// Quanto a avaliação mudaria ao aplicar o movimento.
+    fn move_delta(&self, mv: &Move) -> Weight {
+        if let Move::Flip(i) = *mv {
+            return self.flip_delta(i);
+        }
+        let mut s = self.clone();
+        s.apply(mv);
+        s.value() - self.value()
+    }
+    /// Aplica o primeiro movimento da vizinhança que melhora a solução. Devolve se encontrou
+    /// algum.
+    fn improve(&mut self, neighbourhood: Neighbourhood) -> bool {
+        let Some(mv) = self
+            .moves(neighbourhood)
+            .find(|mv| self.move_delta(mv).0 > crate::IMPROVEMENT)
+        else {
+            return false;
+        };
+        self.apply(&mv);
+        true
+    }
+    /// Indica se a solução continuaria viável após aplicar o movimento.
+    fn move_is_feasible(&self, mv: &Move) -> bool {
+        if let Move::Flip(i) = *mv {
+            return self.flip_is_feasible(i);
+        }
+        let mut s = self.clone();
+        s.apply(mv);
+        s.is_feasible()
+    }
+    /// Aplica o movimento na própria solução.
+    fn apply(&mut self, mv: &Move) {
+        for i in mv.flips() {
+            self.flip_in_place(i);
+        }
+    }
+    /// Cadeia de ejeção que adiciona `added` e remove, na ordem dada, os ítens de `ejectable`
+    /// até que a solução seja viável. `None` se não houver como torná-la viável.
+    fn ejection_chain(&self, added: usize, ejectable: &[usize]) -> Option<Move> {
+        let mut s = self.clone();
+        s.flip_in_place(added);
+        let mut removed = Vec::new();
+        for &i in ejectable {
+            if s.is_feasible() {
+                break;
+            }
+            s.flip_in_place(i);
+            removed.push(i);
+        }
+        s.is_feasible()
+            .then_some(Move::EjectionChain { added, removed })
+    }
+    /// Ítens escolhidos em ordem crescente de razão valor/peso, os primeiros a serem ejetados.
+    fn by_worst_ratio(&self) -> Vec<usize> {
+        let mut chosen = self.items().iter_ones().collect::<Vec<_>>();
+        chosen.sort_by_key(|i| self.ratio(*i));
+        chosen
+    }
+    /// Todos os movimentos da vizinhança.
+    fn moves(&self, neighbourhood: Neighbourhood) -> Box<dyn Iterator<Item = Move> + '_> {
+        let n = self.item_count();
+        match neighbourhood {
+            Neighbourhood::Flip => Box::new((0..n).map(Move::Flip)),
+            Neighbourhood::Swap => Box::new(self.items().iter_ones().flat_map(move |removed| {
+                self.items()
+                    .iter_zeros()
+                    .map(move |added| Move::Swap { removed, added })
+            })),
+            Neighbourhood::TwoFlip => {
+                Box::new((0..n).flat_map(move |i| (i + 1..n).map(move |j| Move::TwoFlip(i, j))))
+            }
+            Neighbourhood::EjectionChain => {
+                let ejectable = self.by_worst_ratio();
+                Box::new(
+                    self.items()
+                        .iter_zeros()
+                        .filter_map(move |added| self.ejection_chain(added, &ejectable)),
+                )
+            }
+        }
+    }
+    /// Um movimento aleatório da vizinhança. Se a vizinhança for vazia (por exemplo, trocas
+    /// em uma solução vazia), inverte um ítem aleatório.
+    fn random_move(&self, neighbourhood: Neighbourhood, rand: &mut impl Rng) -> Move
+    where
+        Self: Sized,
+    {
+        let n = self.item_count();
+        let mv = match neighbourhood {
+            Neighbourhood::Flip => None,
+            Neighbourhood::Swap => self.items().iter_ones().choose(rand).and_then(|removed| {
+                let added = self.items().iter_zeros().choose(rand)?;
+                Some(Move::Swap { removed, added })
+            }),
+            Neighbourhood::TwoFlip if n > 1 => {
+                let i = rand.gen_range(0..n);
+                // sorteia entre os outros ítens
+                let j = (i + rand.gen_range(1..n)) % n;
+                Some(Move::TwoFlip(i, j))
+            }
+            Neighbourhood::TwoFlip => None,
+            Neighbourhood::EjectionChain => self
+                .items()
+                .iter_zeros()
+                .choose(rand)
+                .and_then(|added| self.ejection_chain(added, &self.by_worst_ratio())),
+        };
+        mv.unwrap_or_else(|| Move::Flip(rand.gen_range(0..n)))
+    }
+}
 
 #[derive(Debug, Clone, Copy)]
 pub struct Item {
@@ -37,8 +281,17 @@ pub fn read_knapsack(file: &mut dyn BufRead) -> std::io::Result<(UWeight, Vec<It
 }
 
 pub trait EvaluationMethod: Sized + Clone + Copy {
-    fn evaluate_solution(&self, solution: &Solution<'_, Self>) -> Weight;
+    /// Avalia uma solução a partir da soma dos valores e da soma dos pesos de seus ítens.
+    fn evaluate(&self, total_value: UWeight, total_weight: UWeight) -> Weight;
+    fn evaluate_solution(&self, solution: &Solution<'_, Self>) -> Weight {
+        self.evaluate(solution.total_value(), solution.total_weight())
+    }
     fn max_weight(&self) -> UWeight;
+    /// Informa se a solução corrente da busca é viável, para métodos cuja penalidade se adapta
+    /// ao andamento da busca. Devolve se a avaliação mudou.
+    fn adapt(&mut self, _feasible: bool) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -48,10 +301,9 @@ pub struct WithPenalty {
 }
 
 impl EvaluationMethod for WithPenalty {
-    fn evaluate_solution(&self, solution: &Solution<'_, Self>) -> Weight {
-        let total_val = solution.total_value();
-        let excess = solution.total_weight().sub(self.max_weight).max(0.into());
-        (total_val - (self.penalty * excess))
+    fn evaluate(&self, total_value: UWeight, total_weight: UWeight) -> Weight {
+        let excess = total_weight.sub(self.max_weight).max(0.into());
+        total_value - (self.penalty * excess)
     }
     fn max_weight(&self) -> UWeight {
         self.max_weight
@@ -64,20 +316,147 @@ pub struct ByTotalValue {
 }
 
 impl EvaluationMethod for ByTotalValue {
-    fn evaluate_solution(&self, solution: &Solution<'_, Self>) -> Weight {
-        solution.total_value()
+    fn evaluate(&self, total_value: UWeight, _total_weight: UWeight) -> Weight {
+        total_value
+    }
+    fn max_weight(&self) -> UWeight {
+        self.max_weight
+    }
+}
+
+/// Penalidade que se adapta à busca: é multiplicada por `factor` quando a solução corrente fica
+/// inviável por `window` iterações seguidas e dividida por `factor` quando fica viável pelo mesmo
+/// tempo, sempre entre `min_penalty` e `max_penalty`.
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptivePenalty {
+    pub max_weight: UWeight,
+    pub penalty: UWeight,
+    pub min_penalty: UWeight,
+    pub max_penalty: UWeight,
+    pub factor: f64,
+    pub window: u32,
+    /// Iterações seguidas viáveis (positivo) ou inviáveis (negativo).
+    streak: i64,
+}
+
+impl AdaptivePenalty {
+    pub fn new(max_weight: UWeight, penalty: UWeight) -> Self {
+        Self {
+            max_weight,
+            penalty,
+            min_penalty: penalty / 100.0,
+            max_penalty: penalty * 100.0,
+            factor: 2.0,
+            window: 10,
+            streak: 0,
+        }
+    }
+}
+
+impl EvaluationMethod for AdaptivePenalty {
+    fn evaluate(&self, total_value: UWeight, total_weight: UWeight) -> Weight {
+        WithPenalty {
+            max_weight: self.max_weight,
+            penalty: self.penalty,
+        }
+        .evaluate(total_value, total_weight)
+    }
+    fn max_weight(&self) -> UWeight {
+        self.max_weight
+    }
+    fn adapt(&mut self, feasible: bool) -> bool {
+        self.streak = match (feasible, self.streak) {
+            (true, streak) => streak.max(0) + 1,
+            (false, streak) => streak.min(0) - 1,
+        };
+        let window = self.window as i64;
+        let penalty = if self.streak >= window {
+            (self.penalty / self.factor).max(self.min_penalty)
+        } else if self.streak <= -window {
+            (self.penalty * self.factor).min(self.max_penalty)
+        } else {
+            return false;
+        };
+        self.streak = 0;
+        let changed = penalty != self.penalty;
+        self.penalty = penalty;
+        changed
+    }
+}
+
+/// Oscilação estratégica: a penalidade cai a cada iteração até que a busca atravesse a
+/// fronteira de viabilidade e passe `depth` iterações do lado inviável; então sobe a cada
+/// iteração até que a busca passe `depth` iterações do lado viável, e assim por diante.
+#[derive(Debug, Clone, Copy)]
+pub struct StrategicOscillation {
+    pub max_weight: UWeight,
+    pub penalty: UWeight,
+    pub min_penalty: UWeight,
+    pub max_penalty: UWeight,
+    pub factor: f64,
+    pub depth: u32,
+    /// Se a busca está sendo levada para o lado inviável.
+    outward: bool,
+    /// Iterações já passadas do outro lado da fronteira na fase atual.
+    crossed: u32,
+}
+
+impl StrategicOscillation {
+    pub fn new(max_weight: UWeight, penalty: UWeight) -> Self {
+        Self {
+            max_weight,
+            penalty,
+            min_penalty: penalty / 100.0,
+            max_penalty: penalty * 100.0,
+            factor: 1.1,
+            depth: 5,
+            outward: true,
+            crossed: 0,
+        }
+    }
+}
+
+impl EvaluationMethod for StrategicOscillation {
+    fn evaluate(&self, total_value: UWeight, total_weight: UWeight) -> Weight {
+        WithPenalty {
+            max_weight: self.max_weight,
+            penalty: self.penalty,
+        }
+        .evaluate(total_value, total_weight)
     }
     fn max_weight(&self) -> UWeight {
         self.max_weight
     }
+    fn adapt(&mut self, feasible: bool) -> bool {
+        // a fase termina depois de `depth` iterações do outro lado
+        if feasible != self.outward {
+            self.crossed += 1;
+            if self.crossed >= self.depth {
+                self.outward = !self.outward;
+                self.crossed = 0;
+            }
+        }
+        let penalty = if self.outward {
+            (self.penalty / self.factor).max(self.min_penalty)
+        } else {
+            (self.penalty * self.factor).min(self.max_penalty)
+        };
+        let changed = penalty != self.penalty;
+        self.penalty = penalty;
+        changed
+    }
 }
 
+/// Solução binária que mantém as somas de valores e pesos dos ítens escolhidos, de forma que
+/// inverter ou trocar ítens seja avaliado e aplicado em O(1).
 #[derive(Clone)]
 pub struct Solution<'ks, E: EvaluationMethod = WithPenalty> {
     pub items: BitVec,
     pub value: Weight,
     pub knapsack: &'ks [Item],
     pub eval_method: E,
+    total_value: UWeight,
+    total_weight: UWeight,
 }
 
 impl<'ks, E> Solution<'ks, E>
@@ -85,11 +464,15 @@ where
     E: EvaluationMethod,
 {
     pub fn new(knapsack: &'ks [Item], items: BitVec, eval_method: E) -> Self {
+        let total_value = items.iter_ones().map(|index| knapsack[index].value).sum();
+        let total_weight = items.iter_ones().map(|index| knapsack[index].weight).sum();
         let mut this = Self {
             value: 0.0.into(),
             eval_method,
             items,
             knapsack,
+            total_value,
+            total_weight,
         };
         this.value = this.eval_method.evaluate_solution(&this);
         this
@@ -104,50 +487,159 @@ where
         // ordena por valor do ítem
         sorted.sort_by(|(_, item), (_, item2)| item.value.total_cmp(&item2.value));
         while let Some((i, _)) = sorted.pop() {
-            let new = this.flip(i);
-            if this < new {
-                this = new
+            if this.flip_delta(i).0 > 0.0 {
+                this.flip_in_place(i);
             }
         }
         this
     }
     pub fn total_value(&self) -> UWeight {
-        self.items
-            .iter_ones()
-            .map(|index| self.knapsack[index].value)
-            .sum()
+        self.total_value
     }
     pub fn total_weight(&self) -> UWeight {
-        self.items
-            .iter_ones()
-            .map(|index| self.knapsack[index].weight)
-            .sum()
+        self.total_weight
+    }
+    /// Variação de (valor, peso) ao inverter o ítem `index`.
+    fn flip_change(&self, index: usize) -> (UWeight, UWeight) {
+        let item = self.knapsack[index];
+        if self.items[index] {
+            (-item.value, -item.weight)
+        } else {
+            (item.value, item.weight)
+        }
+    }
+    /// Avaliação da solução com as somas alteradas por `(value, weight)`.
+    fn evaluate_change(&self, (value, weight): (UWeight, UWeight)) -> Weight {
+        self.eval_method
+            .evaluate(self.total_value + value, self.total_weight + weight)
+    }
+    /// Quanto a avaliação mudaria ao inverter o ítem `index`.
+    pub fn flip_delta(&self, index: usize) -> Weight {
+        self.evaluate_change(self.flip_change(index)) - self.value
+    }
+    /// Quanto a avaliação mudaria ao remover o ítem `removed` e adicionar o ítem `added`, que devem
+    /// estar, respectivamente, dentro e fora da solução.
+    pub fn swap_delta(&self, removed: usize, added: usize) -> Weight {
+        debug_assert!(self.items[removed] && !self.items[added]);
+        let (removed, added) = (self.knapsack[removed], self.knapsack[added]);
+        self.evaluate_change((added.value - removed.value, added.weight - removed.weight))
+            - self.value
+    }
+    /// Peso total após inverter o ítem `index`.
+    pub fn flip_weight(&self, index: usize) -> UWeight {
+        self.total_weight + self.flip_change(index).1
+    }
+    pub fn flip_in_place(&mut self, index: usize) {
+        let (value, weight) = self.flip_change(index);
+        self.value = self.evaluate_change((value, weight));
+        self.total_value += value;
+        self.total_weight += weight;
+        let val = !self.items[index];
+        self.items.set(index, val);
+    }
+    /// Variação de (valor, peso) ao aplicar o movimento.
+    fn move_change(&self, mv: &Move) -> (UWeight, UWeight) {
+        mv.flips()
+            .map(|i| self.flip_change(i))
+            .fold((0.0.into(), 0.0.into()), |(v, w), (dv, dw)| {
+                (v + dv, w + dw)
+            })
+    }
+    /// Remove o ítem `removed` e adiciona o ítem `added` na própria solução.
+    pub fn swap_in_place(&mut self, removed: usize, added: usize) {
+        self.flip_in_place(removed);
+        self.flip_in_place(added);
     }
     pub fn flip(&self, index: usize) -> Self {
-        let mut flipped = self.items.clone();
-        let val = !flipped[index];
-        flipped.set(index, val);
-        Self::new(self.knapsack, flipped, self.eval_method)
+        let mut flipped = self.clone();
+        flipped.flip_in_place(index);
+        flipped
     }
     pub fn best_neighbour(&self, taboos: &mut BitVec, best_value: Weight) -> Option<Self> {
-        let mut current_best = None;
+        let mut current_best: Option<(usize, Weight)> = None;
         for i in 0..self.knapsack.len() {
-            let s_prime = self.flip(i);
+            let delta = self.flip_delta(i);
             // critério de aspiração
-            if s_prime.value > best_value {
-                return Some(s_prime);
+            if self.value + delta > best_value {
+                return Some(self.flip(i));
             }
             // Se o movimento for taboo, olha o próximo
             if taboos[i] {
                 continue;
             }
             match current_best {
-                None => current_best = Some(s_prime),
-                Some(cur_best) if s_prime > cur_best => current_best = Some(s_prime),
-                _ => (),
+                Some((_, cur_best)) if delta <= cur_best => (),
+                _ => current_best = Some((i, delta)),
             }
         }
-        current_best
+        current_best.map(|(i, _)| self.flip(i))
+    }
+}
+
+impl<E: EvaluationMethod> KnapsackSolution for Solution<'_, E> {
+    fn item_count(&self) -> usize {
+        self.knapsack.len()
+    }
+    fn items(&self) -> &BitVec {
+        &self.items
+    }
+    fn value(&self) -> Weight {
+        self.value
+    }
+    fn total_value(&self) -> UWeight {
+        Solution::total_value(self)
+    }
+    fn total_weight(&self) -> UWeight {
+        Solution::total_weight(self)
+    }
+    fn item_value(&self, index: usize) -> UWeight {
+        self.knapsack[index].value
+    }
+    fn is_feasible(&self) -> bool {
+        self.total_weight() <= self.eval_method.max_weight()
+    }
+    fn flip(&self, index: usize) -> Self {
+        Solution::flip(self, index)
+    }
+    fn flip_delta(&self, index: usize) -> Weight {
+        Solution::flip_delta(self, index)
+    }
+    fn flip_is_feasible(&self, index: usize) -> bool {
+        self.flip_weight(index) <= self.eval_method.max_weight()
+    }
+    fn flip_in_place(&mut self, index: usize) {
+        Solution::flip_in_place(self, index)
+    }
+    fn adapt_penalty(&mut self) {
+        let feasible = KnapsackSolution::is_feasible(self);
+        if self.eval_method.adapt(feasible) {
+            self.value = self.eval_method.evaluate_solution(self);
+        }
+    }
+    fn empty(&self) -> Self {
+        Self::empty(self.knapsack, self.eval_method)
+    }
+    fn item_weight(&self, index: usize) -> UWeight {
+        self.knapsack[index].weight
+    }
+    fn move_delta(&self, mv: &Move) -> Weight {
+        self.evaluate_change(self.move_change(mv)) - self.value
+    }
+    fn move_is_feasible(&self, mv: &Move) -> bool {
+        self.total_weight + self.move_change(mv).1 <= self.eval_method.max_weight()
+    }
+    fn ejection_chain(&self, added: usize, ejectable: &[usize]) -> Option<Move> {
+        let max_weight = self.eval_method.max_weight();
+        let mut weight = self.total_weight + self.knapsack[added].weight;
+        let mut removed = Vec::new();
+        for &i in ejectable {
+            if weight <= max_weight {
+                break;
+            }
+            weight -= self.knapsack[i].weight;
+            removed.push(i);
+        }
+        (weight <= max_weight).then_some(Move::EjectionChain { added, removed })
     }
 }
 
@@ -168,3 +660,94 @@ impl<E: EvaluationMethod> std::cmp::PartialOrd for Solution<'_, E> {
         self.value.partial_cmp(&other.value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn random_items(n: usize, rand: &mut StdRng) -> Vec<Item> {
+        (0..n)
+            .map(|_| Item {
+                value: (rand.gen_range(1..50) as f64).into(),
+                weight: (rand.gen_range(1..30) as f64).into(),
+            })
+            .collect()
+    }
+
+    /// Avaliação completa de uma solução com os ítens dados.
+    fn evaluate(s: &Solution, items: BitVec) -> Weight {
+        Solution::new(s.knapsack, items, s.eval_method).value
+    }
+
+    #[test]
+    fn reads_knapsack() {
+        let input = "3 10\n5 4\n6 3\n\n7 2\n";
+        let (capacity, items) = read_knapsack(&mut input.as_bytes()).unwrap();
+        assert_eq!(capacity, 10.0);
+        let pairs = items
+            .iter()
+            .map(|item| (item.value.0, item.weight.0))
+            .collect::<Vec<_>>();
+        assert_eq!(pairs, [(5.0, 4.0), (6.0, 3.0), (7.0, 2.0)]);
+    }
+
+    #[test]
+    fn deltas_match_evaluation() {
+        let mut rand = StdRng::seed_from_u64(3);
+        for _ in 0..50 {
+            let knapsack = random_items(8, &mut rand);
+            let params = WithPenalty {
+                max_weight: 40.0.into(),
+                penalty: 2.0.into(),
+            };
+            let items = (0..8).map(|_| rand.gen_bool(0.5)).collect::<BitVec>();
+            let s = Solution::new(&knapsack, items, params);
+
+            for i in 0..8 {
+                let mut flipped = s.items.clone();
+                let val = !flipped[i];
+                flipped.set(i, val);
+                let expected = evaluate(&s, flipped) - s.value;
+                assert_eq!(s.flip_delta(i), expected, "flip {i} em {s:?}");
+                assert_eq!(s.flip(i).value, s.value + expected);
+            }
+            for removed in s.items.iter_ones() {
+                for added in s.items.iter_zeros() {
+                    let mut swapped = s.items.clone();
+                    swapped.set(removed, false);
+                    swapped.set(added, true);
+                    let expected = evaluate(&s, swapped) - s.value;
+                    assert_eq!(s.swap_delta(removed, added), expected);
+                    let mv = Move::Swap { removed, added };
+                    assert_eq!(s.move_delta(&mv), expected);
+                }
+            }
+            for mv in s.moves(Neighbourhood::TwoFlip) {
+                let mut s_prime = s.clone();
+                s_prime.apply(&mv);
+                let expected = evaluate(&s, s_prime.items.clone()) - s.value;
+                assert_eq!(s.move_delta(&mv), expected, "{mv:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn ejection_chain_is_feasible() {
+        let mut rand = StdRng::seed_from_u64(5);
+        let knapsack = random_items(10, &mut rand);
+        let params = WithPenalty {
+            max_weight: 50.0.into(),
+            penalty: 2.0.into(),
+        };
+        let s = Solution::greedy(&knapsack, params);
+        let s = repair::decode(&s);
+        for mv in s.moves(Neighbourhood::EjectionChain) {
+            let mut s_prime = s.clone();
+            s_prime.apply(&mv);
+            assert!(s.move_is_feasible(&mv));
+            assert!(KnapsackSolution::is_feasible(&s_prime), "{mv:?}");
+        }
+    }
+}
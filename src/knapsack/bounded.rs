@@ -0,0 +1,447 @@
+//! Problemas da mochila limitada e ilimitada: cada ítem pode ser escolhido várias vezes, até a
+//! quantidade disponível ou sem limite.
+use std::io::BufRead;
+
+use bitvec::{bitvec, vec::BitVec};
+use rand::{seq::IteratorRandom, Rng};
+
+use super::{EvaluationMethod, UWeight, Weight, WithPenalty};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Item {
+    pub weight: UWeight,
+    pub value: UWeight,
+    /// Quantidade de cópias disponíveis, ou `None` se forem ilimitadas.
+    pub available: Option<u32>,
+}
+
+impl Item {
+    /// Máximo de cópias que cabem em `capacity`, respeitando a disponibilidade.
+    ///
+    /// Um ítem ilimitado sem peso tornaria o problema ilimitado, e por isso é rejeitado por
+    /// [`read_bounded_knapsack`]; aqui nenhuma cópia dele é considerada.
+    fn max_copies(&self, capacity: UWeight) -> u32 {
+        if self.weight.0 <= 0.0 {
+            return self.available.unwrap_or(0);
+        }
+        let fitting = (capacity / self.weight).0.max(0.0).floor() as u32;
+        self.available
+            .map_or(fitting, |available| available.min(fitting))
+    }
+}
+
+/// Lê uma instância no formato de [`super::read_knapsack`] com uma terceira coluna com a
+/// quantidade de cópias disponíveis de cada ítem, ou `inf` para cópias ilimitadas:
+///
+/// ```text
+/// n wmax
+/// v1 w1 c1
+/// ...
+/// vn wn cn
+/// ```
+///
+/// Números inválidos, linhas com outra quantidade de colunas, quantidades que não são inteiros
+/// não negativos e ítens ilimitados sem peso resultam em um erro
+/// [`std::io::ErrorKind::InvalidData`].
+pub fn read_bounded_knapsack(file: &mut dyn BufRead) -> std::io::Result<(UWeight, Vec<Item>)> {
+    let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+
+    let mut lines = vec![];
+    for line in file.lines() {
+        let nums = line?
+            .split_whitespace()
+            .map(|num| {
+                num.parse::<UWeight>()
+                    .map_err(|_| invalid(format!("Número inválido: {num}")))
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+        if !nums.is_empty() {
+            lines.push(nums);
+        }
+    }
+
+    let Some((info, lines)) = lines.split_first() else {
+        return Err(invalid("Arquivo vazio".to_string()));
+    };
+    let &[_, weight] = &info[..] else {
+        return Err(invalid(format!(
+            "A primeira linha deve ter 2 colunas, mas tem {}",
+            info.len()
+        )));
+    };
+    let items = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let &[value, weight, available] = &line[..] else {
+                return Err(invalid(format!(
+                    "O ítem {i} deve ter 3 colunas, mas tem {}",
+                    line.len()
+                )));
+            };
+            let available = if available == f64::INFINITY {
+                None
+            } else if available.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&available.0) {
+                Some(available.0 as u32)
+            } else {
+                return Err(invalid(format!(
+                    "A quantidade do ítem {i} deve ser um inteiro não negativo ou inf, mas é {available}"
+                )));
+            };
+            if available.is_none() && weight.0 <= 0.0 {
+                return Err(invalid(format!("O ítem {i} é ilimitado e não tem peso")));
+            }
+            Ok(Item {
+                value,
+                weight,
+                available,
+            })
+        })
+        .collect::<std::io::Result<_>>()?;
+    Ok((weight, items))
+}
+
+/// Movimentos sobre a quantidade de cópias de um ítem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    /// Adiciona uma cópia do ítem.
+    Increment(usize),
+    /// Remove uma cópia do ítem.
+    Decrement(usize),
+}
+
+/// Solução representada pela quantidade de cópias escolhidas de cada ítem. Mantém as somas dos
+/// valores e dos pesos, então os movimentos são avaliados e aplicados em O(1).
+#[derive(Clone)]
+pub struct Solution<'ks, E: EvaluationMethod = WithPenalty> {
+    pub counts: Vec<u32>,
+    pub value: Weight,
+    pub knapsack: &'ks [Item],
+    pub eval_method: E,
+    total_value: UWeight,
+    total_weight: UWeight,
+}
+
+impl<'ks, E> Solution<'ks, E>
+where
+    E: EvaluationMethod,
+{
+    pub fn new(knapsack: &'ks [Item], counts: Vec<u32>, eval_method: E) -> Self {
+        let (total_value, total_weight) = counts
+            .iter()
+            .zip(knapsack)
+            .map(|(count, item)| (item.value * *count as f64, item.weight * *count as f64))
+            .fold(
+                (UWeight::from(0.0), UWeight::from(0.0)),
+                |(v, w), (dv, dw)| (v + dv, w + dw),
+            );
+        Self {
+            value: eval_method.evaluate(total_value, total_weight),
+            eval_method,
+            counts,
+            knapsack,
+            total_value,
+            total_weight,
+        }
+    }
+    pub fn empty(knapsack: &'ks [Item], eval_method: E) -> Self {
+        Self::new(knapsack, vec![0; knapsack.len()], eval_method)
+    }
+    /// Adiciona os ítens em ordem decrescente de razão valor/peso, levando de cada um o máximo
+    /// de cópias que couber.
+    pub fn greedy(knapsack: &'ks [Item], eval_method: E) -> Self {
+        let mut sorted = (0..knapsack.len()).collect::<Vec<_>>();
+        sorted.sort_by_key(|i| std::cmp::Reverse(knapsack[*i].value / knapsack[*i].weight));
+
+        let mut counts = vec![0; knapsack.len()];
+        let mut remaining = eval_method.max_weight();
+        for i in sorted {
+            let item = &knapsack[i];
+            counts[i] = item.max_copies(remaining);
+            remaining -= item.weight * counts[i] as f64;
+        }
+        Self::new(knapsack, counts, eval_method)
+    }
+    pub fn total_value(&self) -> UWeight {
+        self.total_value
+    }
+    pub fn total_weight(&self) -> UWeight {
+        self.total_weight
+    }
+    pub fn is_feasible(&self) -> bool {
+        self.total_weight <= self.eval_method.max_weight()
+    }
+    /// Indica se o movimento pode ser aplicado: ainda há cópias disponíveis para adicionar, ou
+    /// alguma cópia na solução para remover.
+    pub fn is_valid(&self, mv: Move) -> bool {
+        match mv {
+            Move::Increment(i) => self.knapsack[i]
+                .available
+                .is_none_or(|available| self.counts[i] < available),
+            Move::Decrement(i) => self.counts[i] > 0,
+        }
+    }
+    /// Variação de (valor, peso) causada pelo movimento.
+    fn move_change(&self, mv: Move) -> (UWeight, UWeight) {
+        match mv {
+            Move::Increment(i) => (self.knapsack[i].value, self.knapsack[i].weight),
+            Move::Decrement(i) => (-self.knapsack[i].value, -self.knapsack[i].weight),
+        }
+    }
+    /// Quanto a avaliação mudaria ao aplicar o movimento, em O(1).
+    pub fn move_delta(&self, mv: Move) -> Weight {
+        let (value, weight) = self.move_change(mv);
+        self.eval_method
+            .evaluate(self.total_value + value, self.total_weight + weight)
+            - self.value
+    }
+    /// Aplica o movimento na própria solução, em O(1). O movimento deve ser válido.
+    pub fn apply_in_place(&mut self, mv: Move) {
+        debug_assert!(self.is_valid(mv), "Movimento inválido: {mv:?}");
+        let (value, weight) = self.move_change(mv);
+        match mv {
+            Move::Increment(i) => self.counts[i] += 1,
+            Move::Decrement(i) => self.counts[i] -= 1,
+        }
+        self.total_value += value;
+        self.total_weight += weight;
+        self.value = self
+            .eval_method
+            .evaluate(self.total_value, self.total_weight);
+    }
+    /// Adiciona uma cópia do ítem `index`, caso ainda haja cópias disponíveis.
+    pub fn increment(&self, index: usize) -> Option<Self> {
+        self.apply(Move::Increment(index))
+    }
+    /// Remove uma cópia do ítem `index`, caso haja alguma na solução.
+    pub fn decrement(&self, index: usize) -> Option<Self> {
+        self.apply(Move::Decrement(index))
+    }
+    fn apply(&self, mv: Move) -> Option<Self> {
+        if !self.is_valid(mv) {
+            return None;
+        }
+        let mut new = self.clone();
+        new.apply_in_place(mv);
+        Some(new)
+    }
+    /// Incrementa ou decrementa um ítem aleatório. `None` se nenhum ítem puder ser alterado,
+    /// isto é, se não houver cópias disponíveis de nenhum ítem.
+    pub fn random_move(&self, rand: &mut impl Rng) -> Option<Move> {
+        // ítens sem cópias disponíveis e fora da solução não têm vizinhos
+        let index = (0..self.knapsack.len())
+            .filter(|&i| self.counts[i] > 0 || self.knapsack[i].available != Some(0))
+            .choose(rand)?;
+        let (increment, decrement) = (Move::Increment(index), Move::Decrement(index));
+        let (first, second) = if rand.gen() {
+            (increment, decrement)
+        } else {
+            (decrement, increment)
+        };
+        Some(if self.is_valid(first) { first } else { second })
+    }
+    /// Solução vizinha por um movimento aleatório de [`Solution::random_move`].
+    pub fn random_neighbour(&self, rand: &mut impl Rng) -> Option<Self> {
+        self.apply(self.random_move(rand)?)
+    }
+}
+
+/// Resolve o problema de forma exata por programação dinâmica sobre a capacidade, em
+/// O(W · Σ log cᵢ) de tempo e memória, em que `cᵢ` é o máximo de cópias do ítem `i` que cabem na
+/// mochila.
+///
+/// Cada ítem é dividido em pacotes de 1, 2, 4, ... cópias, que formam uma mochila 0/1
+/// equivalente. Os pesos e a capacidade devem ser inteiros.
+pub fn dynamic_programming<E: EvaluationMethod>(
+    knapsack: &[Item],
+    eval_method: E,
+) -> Solution<'_, E> {
+    let capacity = eval_method.max_weight().0.floor() as usize;
+    let mut counts = vec![0; knapsack.len()];
+
+    // pacotes (ítem, cópias)
+    let mut packs = Vec::new();
+    for (i, item) in knapsack.iter().enumerate() {
+        assert!(item.weight.fract() == 0.0, "Pesos devem ser inteiros");
+        if item.weight == 0.0 {
+            // ítens sem peso nunca pioram a solução
+            if item.value.0 > 0.0 {
+                counts[i] = item.available.expect("Ítem ilimitado sem peso");
+            }
+            continue;
+        }
+        let mut remaining = item.max_copies((capacity as f64).into());
+        let mut size = 1;
+        while remaining > 0 {
+            let pack = size.min(remaining);
+            packs.push((i, pack));
+            remaining -= pack;
+            size *= 2;
+        }
+    }
+
+    let mut best = vec![Weight::from(0.0); capacity + 1];
+    // `taken[p * (capacity + 1) + c]` indica se o pacote `p` foi usado no ótimo com capacidade `c`
+    let mut taken: BitVec = bitvec![0; packs.len() * (capacity + 1)];
+    for (p, &(i, copies)) in packs.iter().enumerate() {
+        let weight = knapsack[i].weight.0 as usize * copies as usize;
+        let value = knapsack[i].value * copies as f64;
+        for c in (weight..=capacity).rev() {
+            if best[c - weight] + value > best[c] {
+                best[c] = best[c - weight] + value;
+                taken.set(p * (capacity + 1) + c, true);
+            }
+        }
+    }
+
+    // Reconstrói a solução percorrendo os pacotes de trás para frente.
+    let mut c = capacity;
+    for (p, &(i, copies)) in packs.iter().enumerate().rev() {
+        if taken[p * (capacity + 1) + c] {
+            counts[i] += copies;
+            c -= knapsack[i].weight.0 as usize * copies as usize;
+        }
+    }
+    Solution::new(knapsack, counts, eval_method)
+}
+
+impl<E: EvaluationMethod> std::fmt::Debug for Solution<'_, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.counts)
+    }
+}
+
+impl<E: EvaluationMethod> std::cmp::PartialEq for Solution<'_, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value.eq(&other.value)
+    }
+}
+
+impl<E: EvaluationMethod> std::cmp::PartialOrd for Solution<'_, E> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn reads_bounded_knapsack() {
+        let input = "2 10\n5 4 2\n6 3 inf\n";
+        let (capacity, items) = read_bounded_knapsack(&mut input.as_bytes()).unwrap();
+        assert_eq!(capacity, 10.0);
+        assert_eq!(items[0].available, Some(2));
+        assert_eq!(items[1].available, None);
+        assert_eq!((items[1].value.0, items[1].weight.0), (6.0, 3.0));
+    }
+
+    #[test]
+    fn rejects_invalid_bounded_knapsack() {
+        for input in [
+            "2 10\n5 4\n6 3\n",
+            "1 10\n5 0 inf\n",
+            "",
+            "1 10\n5 4 1.5\n",
+            "1 10\n5 4 -1\n",
+            "1 10\n5 4 -inf\n",
+            "1 10\n5 x 1\n",
+        ] {
+            let err = read_bounded_knapsack(&mut input.as_bytes()).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData, "{input:?}");
+        }
+    }
+
+    /// Melhor valor viável, enumerando todas as quantidades de cópias.
+    fn brute_force(knapsack: &[Item], capacity: UWeight, index: usize) -> UWeight {
+        let Some(item) = knapsack.get(index) else {
+            return 0.0.into();
+        };
+        (0..=item.max_copies(capacity))
+            .map(|copies| {
+                let copies = copies as f64;
+                item.value * copies
+                    + brute_force(knapsack, capacity - item.weight * copies, index + 1)
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn dynamic_programming_matches_brute_force() {
+        let mut rand = StdRng::seed_from_u64(17);
+        for _ in 0..100 {
+            let knapsack = (0..5)
+                .map(|_| Item {
+                    value: (rand.gen_range(1..30) as f64).into(),
+                    weight: (rand.gen_range(0..10) as f64).into(),
+                    available: rand.gen_bool(0.7).then(|| rand.gen_range(0..4)),
+                })
+                // ítens ilimitados sem peso são rejeitados pelo leitor
+                .filter(|item| item.weight.0 > 0.0 || item.available.is_some())
+                .collect::<Vec<_>>();
+            let params = WithPenalty {
+                max_weight: (rand.gen_range(0..25) as f64).into(),
+                penalty: 1.0.into(),
+            };
+            let s = dynamic_programming(&knapsack, params);
+            assert!(s.is_feasible());
+            assert_eq!(
+                s.total_value(),
+                brute_force(&knapsack, params.max_weight, 0),
+                "{knapsack:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn random_neighbour_without_copies() {
+        let knapsack = [Item {
+            value: 1.0.into(),
+            weight: 1.0.into(),
+            available: Some(0),
+        }];
+        let params = WithPenalty {
+            max_weight: 10.0.into(),
+            penalty: 1.0.into(),
+        };
+        let s = Solution::empty(&knapsack, params);
+        assert!(s.random_neighbour(&mut rand::thread_rng()).is_none());
+    }
+
+    #[test]
+    fn moves_match_evaluation() {
+        let mut rand = StdRng::seed_from_u64(47);
+        let knapsack = (0..6)
+            .map(|_| Item {
+                value: (rand.gen_range(1..30) as f64).into(),
+                weight: (rand.gen_range(1..10) as f64).into(),
+                available: rand.gen_bool(0.7).then(|| rand.gen_range(0..4)),
+            })
+            .collect::<Vec<_>>();
+        let params = WithPenalty {
+            max_weight: 15.0.into(),
+            penalty: 2.0.into(),
+        };
+        let mut s = Solution::empty(&knapsack, params);
+        for _ in 0..200 {
+            let mv = s.random_move(&mut rand).unwrap();
+            assert!(s.is_valid(mv));
+            let (delta, before) = (s.move_delta(mv), s.value);
+            s.apply_in_place(mv);
+
+            // reavalia do zero
+            let fresh = Solution::new(&knapsack, s.counts.clone(), params);
+            assert_eq!(before + delta, fresh.value, "{mv:?}");
+            assert_eq!(s.value, fresh.value);
+            assert_eq!(s.total_weight(), fresh.total_weight());
+            assert!(knapsack
+                .iter()
+                .zip(&s.counts)
+                .all(|(item, count)| item.available.is_none_or(|a| *count <= a)));
+        }
+    }
+}
@@ -0,0 +1,407 @@
+//! Problema da mochila de múltipla escolha: os ítens são divididos em classes e a solução escolhe
+//! exatamente um ítem de cada classe.
+use std::io::BufRead;
+
+use rand::{seq::IteratorRandom, Rng};
+
+use super::{EvaluationMethod, Item, UWeight, Weight, WithPenalty};
+
+/// Lê uma instância no formato
+///
+/// ```text
+/// k wmax
+/// n1
+/// v w
+/// ... (n1 linhas)
+/// n2
+/// ...
+/// ```
+///
+/// em que `k` é a quantidade de classes e `ni` a quantidade de ítens da classe `i`. Como a solução
+/// escolhe um ítem de cada classe, classes vazias resultam em um erro
+/// [`std::io::ErrorKind::InvalidData`].
+pub fn read_choice_knapsack(file: &mut dyn BufRead) -> std::io::Result<(UWeight, Vec<Vec<Item>>)> {
+    let mut nums = vec![];
+    for line in file.lines() {
+        // adiciona todos os números no vetor `nums`
+        nums.extend(
+            line?
+                .split_whitespace()
+                .map(|num| num.parse::<UWeight>().expect("Número inválido")),
+        )
+    }
+    let mut nums = nums.into_iter();
+    let mut next = || nums.next().expect("Arquivo terminou antes do esperado");
+
+    let count = next().0 as usize;
+    let weight = next();
+    let classes = (0..count)
+        .map(|c| {
+            let n = next().0 as usize;
+            if n == 0 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("A classe {c} não tem ítens"),
+                ));
+            }
+            Ok((0..n)
+                .map(|_| Item {
+                    value: next(),
+                    weight: next(),
+                })
+                .collect())
+        })
+        .collect::<std::io::Result<_>>()?;
+    Ok((weight, classes))
+}
+
+/// Índices dos ítens da classe que não são dominados, nem por outro ítem (mais leve e mais
+/// valioso) nem por uma combinação convexa de dois outros (dominância da relaxação linear), em
+/// ordem crescente de peso. São os vértices superiores da envoltória convexa em (peso, valor).
+pub fn lp_undominated(class: &[Item]) -> Vec<usize> {
+    let mut sorted = (0..class.len()).collect::<Vec<_>>();
+    // peso crescente; em caso de empate, o mais valioso primeiro
+    sorted.sort_by_key(|i| (class[*i].weight, std::cmp::Reverse(class[*i].value)));
+
+    let mut hull: Vec<usize> = Vec::with_capacity(class.len());
+    for i in sorted {
+        let item = class[i];
+        // dominado por um ítem mais leve e pelo menos tão valioso
+        if hull
+            .last()
+            .is_some_and(|last| class[*last].value >= item.value)
+        {
+            continue;
+        }
+        // remove os ítens que ficam abaixo do segmento entre o anterior e o novo ítem
+        while let [.., a, b] = hull[..] {
+            let (a, b) = (class[a], class[b]);
+            let cross = (b.weight - a.weight) * (item.value - a.value)
+                - (b.value - a.value) * (item.weight - a.weight);
+            if cross < 0.0.into() {
+                break;
+            }
+            hull.pop();
+        }
+        hull.push(i);
+    }
+    hull
+}
+
+/// Solução representada pelo ítem escolhido de cada classe.
+#[derive(Clone)]
+pub struct Solution<'ks, E: EvaluationMethod = WithPenalty> {
+    pub choices: Vec<usize>,
+    pub value: Weight,
+    pub classes: &'ks [Vec<Item>],
+    pub eval_method: E,
+}
+
+impl<'ks, E> Solution<'ks, E>
+where
+    E: EvaluationMethod,
+{
+    pub fn new(classes: &'ks [Vec<Item>], choices: Vec<usize>, eval_method: E) -> Self {
+        let mut this = Self {
+            value: 0.0.into(),
+            eval_method,
+            choices,
+            classes,
+        };
+        this.value = eval_method.evaluate(this.total_value(), this.total_weight());
+        this
+    }
+    /// Construção gulosa pela relaxação linear: parte do ítem mais leve não dominado de cada
+    /// classe e aplica as trocas para o próximo ítem da envoltória em ordem decrescente de
+    /// eficiência (valor ganho por peso adicional), enquanto couberem.
+    pub fn greedy(classes: &'ks [Vec<Item>], eval_method: E) -> Self {
+        let hulls = classes
+            .iter()
+            .map(|class| lp_undominated(class))
+            .collect::<Vec<_>>();
+        let mut choices = hulls.iter().map(|hull| hull[0]).collect::<Vec<_>>();
+        let mut remaining = eval_method.max_weight()
+            - hulls
+                .iter()
+                .zip(classes)
+                .map(|(hull, class)| class[hull[0]].weight)
+                .sum::<UWeight>();
+
+        // melhorias incrementais (eficiência, classe, posição na envoltória); pela convexidade,
+        // as de uma mesma classe já ficam em ordem
+        let mut upgrades = Vec::new();
+        for (c, (hull, class)) in hulls.iter().zip(classes).enumerate() {
+            for (k, pair) in hull.windows(2).enumerate() {
+                let (a, b) = (class[pair[0]], class[pair[1]]);
+                let efficiency = (b.value - a.value) / (b.weight - a.weight);
+                upgrades.push((efficiency, c, k + 1));
+            }
+        }
+        upgrades.sort_by_key(|(efficiency, ..)| std::cmp::Reverse(*efficiency));
+
+        for (_, c, k) in upgrades {
+            let hull = &hulls[c];
+            // só pode subir um degrau se o anterior da mesma classe foi aplicado
+            if choices[c] != hull[k - 1] {
+                continue;
+            }
+            let extra = classes[c][hull[k]].weight - classes[c][hull[k - 1]].weight;
+            if extra <= remaining {
+                remaining -= extra;
+                choices[c] = hull[k];
+            }
+        }
+        Self::new(classes, choices, eval_method)
+    }
+    pub fn total_value(&self) -> UWeight {
+        self.choices
+            .iter()
+            .zip(self.classes)
+            .map(|(choice, class)| class[*choice].value)
+            .sum()
+    }
+    pub fn total_weight(&self) -> UWeight {
+        self.choices
+            .iter()
+            .zip(self.classes)
+            .map(|(choice, class)| class[*choice].weight)
+            .sum()
+    }
+    pub fn is_feasible(&self) -> bool {
+        self.total_weight() <= self.eval_method.max_weight()
+    }
+    /// Troca o ítem escolhido da classe `class` por `item`.
+    pub fn switch(&self, class: usize, item: usize) -> Self {
+        let mut choices = self.choices.clone();
+        choices[class] = item;
+        Self::new(self.classes, choices, self.eval_method)
+    }
+    /// Todas as trocas `(classe, ítem)` que alteram a solução.
+    pub fn moves(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.classes.iter().enumerate().flat_map(move |(c, class)| {
+            (0..class.len())
+                .filter(move |i| *i != self.choices[c])
+                .map(move |i| (c, i))
+        })
+    }
+    /// Troca o ítem escolhido de uma classe aleatória com mais de um ítem. `None` se todas as
+    /// classes tiverem um único ítem.
+    pub fn random_neighbour(&self, rand: &mut impl Rng) -> Option<Self> {
+        let c = (0..self.classes.len())
+            .filter(|c| self.classes[*c].len() > 1)
+            .choose(rand)?;
+        let len = self.classes[c].len();
+        // sorteia entre os outros ítens da classe
+        let i = (self.choices[c] + rand.gen_range(1..len)) % len;
+        Some(self.switch(c, i))
+    }
+}
+
+/// Resolve o problema de forma exata por programação dinâmica sobre a capacidade, em
+/// O(W · n) de tempo e O(W · k) de memória. Os pesos e a capacidade devem ser inteiros.
+///
+/// Devolve `None` se nenhuma escolha couber na mochila.
+pub fn dynamic_programming<E: EvaluationMethod>(
+    classes: &[Vec<Item>],
+    eval_method: E,
+) -> Option<Solution<'_, E>> {
+    let capacity = eval_method.max_weight().0.floor() as usize;
+    let unreachable = Weight::from(f64::NEG_INFINITY);
+
+    // best[c]: melhor valor escolhendo um ítem de cada classe já vista, com peso até c
+    let mut best = vec![Weight::from(0.0); capacity + 1];
+    // chosen[k * (capacity + 1) + c]: ítem da classe k no ótimo com capacidade c
+    let mut chosen = vec![u32::MAX; classes.len() * (capacity + 1)];
+    for (k, class) in classes.iter().enumerate() {
+        let mut next = vec![unreachable; capacity + 1];
+        for (i, item) in class.iter().enumerate() {
+            assert!(item.weight.fract() == 0.0, "Pesos devem ser inteiros");
+            let weight = item.weight.0 as usize;
+            for c in weight..=capacity {
+                let value = best[c - weight] + item.value;
+                if value > next[c] {
+                    next[c] = value;
+                    chosen[k * (capacity + 1) + c] = i as u32;
+                }
+            }
+        }
+        best = next;
+    }
+    if best[capacity] == unreachable {
+        return None;
+    }
+
+    // Reconstrói a solução percorrendo as classes de trás para frente.
+    let mut choices = vec![0; classes.len()];
+    let mut c = capacity;
+    for k in (0..classes.len()).rev() {
+        let i = chosen[k * (capacity + 1) + c] as usize;
+        choices[k] = i;
+        c -= classes[k][i].weight.0 as usize;
+    }
+    Some(Solution::new(classes, choices, eval_method))
+}
+
+impl<E: EvaluationMethod> std::fmt::Debug for Solution<'_, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.choices)
+    }
+}
+
+impl<E: EvaluationMethod> std::cmp::PartialEq for Solution<'_, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value.eq(&other.value)
+    }
+}
+
+impl<E: EvaluationMethod> std::cmp::PartialOrd for Solution<'_, E> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn reads_choice_knapsack() {
+        let input = "2 10\n2\n5 4\n6 3\n1\n7 2\n";
+        let (capacity, classes) = read_choice_knapsack(&mut input.as_bytes()).unwrap();
+        assert_eq!(capacity, 10.0);
+        let sizes = classes.iter().map(Vec::len).collect::<Vec<_>>();
+        assert_eq!(sizes, [2, 1]);
+        assert_eq!((classes[1][0].value.0, classes[1][0].weight.0), (7.0, 2.0));
+    }
+
+    #[test]
+    fn rejects_empty_class() {
+        let input = "2 10\n0\n1\n7 2\n";
+        let err = read_choice_knapsack(&mut input.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    fn items(pairs: &[(f64, f64)]) -> Vec<Item> {
+        pairs
+            .iter()
+            .map(|(weight, value)| Item {
+                value: (*value).into(),
+                weight: (*weight).into(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lp_undominated_hull() {
+        // (peso, valor): 5 e 4 são dominados por ítens, 2 pela combinação de 1 e 3
+        let class = items(&[
+            (1.0, 1.0),
+            (2.0, 5.0),
+            (3.0, 6.0),
+            (4.0, 9.0),
+            (5.0, 8.0),
+            (2.0, 4.0),
+        ]);
+        assert_eq!(lp_undominated(&class), [0, 1, 3]);
+        assert_eq!(lp_undominated(&items(&[(3.0, 2.0)])), [0]);
+    }
+
+    #[test]
+    fn lp_undominated_is_concave() {
+        let mut rand = StdRng::seed_from_u64(31);
+        for _ in 0..200 {
+            let class = (0..rand.gen_range(1..8))
+                .map(|_| (rand.gen_range(0..10) as f64, rand.gen_range(0..10) as f64))
+                .collect::<Vec<_>>();
+            let class = items(&class);
+            let hull = lp_undominated(&class);
+            assert!(!hull.is_empty());
+            // peso e valor estritamente crescentes, eficiência marginal decrescente
+            for pair in hull.windows(2) {
+                let (a, b) = (class[pair[0]], class[pair[1]]);
+                assert!(a.weight < b.weight && a.value < b.value, "{class:?}");
+            }
+            for triple in hull.windows(3) {
+                let [a, b, c] = [0, 1, 2].map(|k| class[triple[k]]);
+                let cross = (b.weight - a.weight) * (c.value - a.value)
+                    - (b.value - a.value) * (c.weight - a.weight);
+                assert!(cross < 0.0.into(), "{class:?}");
+            }
+            // nenhum ítem fora da envoltória fica acima dela
+            for item in &class {
+                let above = hull.windows(2).any(|pair| {
+                    let (a, b) = (class[pair[0]], class[pair[1]]);
+                    a.weight <= item.weight
+                        && item.weight <= b.weight
+                        && (b.weight - a.weight) * (item.value - a.value)
+                            > (b.value - a.value) * (item.weight - a.weight)
+                });
+                let (first, last) = (class[hull[0]], class[*hull.last().unwrap()]);
+                assert!(!above, "{class:?}");
+                assert!(
+                    item.weight > first.weight || item.value <= first.value,
+                    "{class:?}"
+                );
+                assert!(
+                    item.weight < last.weight || item.value <= last.value,
+                    "{class:?}"
+                );
+            }
+        }
+    }
+
+    /// Melhor valor viável, enumerando todas as escolhas.
+    fn brute_force(classes: &[Vec<Item>], capacity: UWeight) -> Option<UWeight> {
+        let Some((class, rest)) = classes.split_first() else {
+            return Some(0.0.into());
+        };
+        class
+            .iter()
+            .filter(|item| item.weight <= capacity)
+            .filter_map(|item| Some(item.value + brute_force(rest, capacity - item.weight)?))
+            .max()
+    }
+
+    #[test]
+    fn dynamic_programming_matches_brute_force() {
+        let mut rand = StdRng::seed_from_u64(19);
+        for _ in 0..100 {
+            let classes = (0..4)
+                .map(|_| {
+                    (0..rand.gen_range(1..4))
+                        .map(|_| Item {
+                            value: (rand.gen_range(1..30) as f64).into(),
+                            weight: (rand.gen_range(0..10) as f64).into(),
+                        })
+                        .collect()
+                })
+                .collect::<Vec<Vec<_>>>();
+            let params = WithPenalty {
+                max_weight: (rand.gen_range(0..25) as f64).into(),
+                penalty: 1.0.into(),
+            };
+            let expected = brute_force(&classes, params.max_weight);
+            let found = dynamic_programming(&classes, params).map(|s| {
+                assert!(s.is_feasible());
+                s.total_value()
+            });
+            assert_eq!(found, expected, "{classes:?}");
+        }
+    }
+
+    #[test]
+    fn random_neighbour_with_single_items() {
+        let classes = [vec![Item {
+            value: 1.0.into(),
+            weight: 1.0.into(),
+        }]];
+        let params = WithPenalty {
+            max_weight: 10.0.into(),
+            penalty: 1.0.into(),
+        };
+        let s = Solution::greedy(&classes, params);
+        assert!(s.random_neighbour(&mut rand::thread_rng()).is_none());
+    }
+}
@@ -0,0 +1,152 @@
+//! Heurísticas construtivas para os problemas da mochila binários. Todas partem de uma solução
+//! vazia e só adicionam ítens que mantêm a solução viável.
+use rand::{seq::SliceRandom, Rng};
+
+use super::KnapsackSolution;
+
+/// Adiciona os ítens em ordem decrescente de razão valor/peso, desde que caibam. Ítens sem peso
+/// e de valor negativo nunca são adicionados.
+pub fn ratio_greedy<S: KnapsackSolution>(empty: &S) -> S {
+    let mut s = empty.clone();
+    let mut sorted = (0..s.item_count()).collect::<Vec<_>>();
+    sorted.sort_by_key(|i| std::cmp::Reverse(s.ratio(*i)));
+    for i in sorted {
+        if s.ratio(i) == f64::NEG_INFINITY {
+            break;
+        }
+        if !s.items()[i] && s.flip_is_feasible(i) {
+            s.flip_in_place(i);
+        }
+    }
+    s
+}
+
+/// O melhor entre a gulosa por razão e a solução com apenas o ítem mais valioso que cabe
+/// sozinho. Para a mochila 0/1 clássica, tem ao menos metade do valor ótimo.
+pub fn greedy_or_best_item<S: KnapsackSolution>(empty: &S) -> S {
+    let greedy = ratio_greedy(empty);
+    let best_item = (0..empty.item_count())
+        .filter(|i| empty.flip_is_feasible(*i))
+        .max_by_key(|i| empty.item_value(*i));
+    match best_item {
+        Some(i) if empty.item_value(i) > greedy.total_value() => empty.flip(i),
+        _ => greedy,
+    }
+}
+
+/// Construção aleatorizada pela razão valor/peso: a cada passo, sorteia um ítem entre os que
+/// cabem e cuja razão é pelo menos `pior + a · (melhor - pior)` (lista restrita de candidatos).
+/// `a` deve estar em [0, 1], e valores fora do intervalo são limitados a ele: com `a = 1` a
+/// construção é gulosa e com `a = 0` é aleatória.
+pub fn randomized_ratio<S: KnapsackSolution>(empty: &S, a: f64, rand: &mut impl Rng) -> S {
+    // com `a > 1` o corte passaria do melhor e a lista restrita ficaria vazia
+    let a = a.clamp(0.0, 1.0);
+    let mut s = empty.clone();
+    let mut candidates = (0..s.item_count())
+        .filter(|i| !s.items()[*i])
+        .map(|i| (i, s.ratio(i)))
+        .collect::<Vec<_>>();
+    // ordena por razão, da maior para a menor
+    candidates.sort_by_key(|(_, ratio)| std::cmp::Reverse(*ratio));
+
+    loop {
+        // como a solução só cresce, ítens que não cabem mais nunca voltam a caber
+        candidates.retain(|(i, _)| s.flip_is_feasible(*i));
+        let (Some((_, best)), Some((_, worst))) = (candidates.first(), candidates.last()) else {
+            break s;
+        };
+        let cutoff = *worst + (*best - *worst) * a;
+        let rcl_len = candidates.partition_point(|(_, ratio)| *ratio >= cutoff);
+        let chosen = rand.gen_range(0..rcl_len);
+        let (i, _) = candidates.remove(chosen);
+        s.flip_in_place(i);
+    }
+}
+
+/// Construtor do GRASP: a cada passo, avalia o ganho de adicionar cada ítem que ainda cabe e
+/// sorteia um entre os de ganho pelo menos `pior + a · (melhor - pior)`, com `a` limitado a
+/// [0, 1] como em [`randomized_ratio`].
+/// Diferente de [`randomized_ratio`], o ganho é a variação da avaliação, então considera
+/// interações entre ítens, como na mochila quadrática.
+pub fn grasp<S: KnapsackSolution>(empty: &S, a: f64, rand: &mut impl Rng) -> S {
+    let a = a.clamp(0.0, 1.0);
+    let mut s = empty.clone();
+    let mut candidates = (0..s.item_count())
+        .filter(|i| !s.items()[*i])
+        .collect::<Vec<_>>();
+
+    loop {
+        candidates.retain(|i| s.flip_is_feasible(*i));
+        let gains = candidates
+            .iter()
+            .map(|i| (*i, s.flip_delta(*i)))
+            .collect::<Vec<_>>();
+        let (Some(best), Some(worst)) = (
+            gains.iter().map(|(_, g)| *g).max(),
+            gains.iter().map(|(_, g)| *g).min(),
+        ) else {
+            break s;
+        };
+        let cutoff = worst + (best - worst) * a;
+        let rcl = gains
+            .iter()
+            .filter(|(_, g)| *g >= cutoff)
+            .collect::<Vec<_>>();
+        let (chosen, _) = **rcl.choose(rand).unwrap();
+        candidates.retain(|i| *i != chosen);
+        s.flip_in_place(chosen);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::knapsack::{Item, Solution, WithPenalty};
+
+    #[test]
+    fn randomized_constructions_clamp_a() {
+        let knapsack = [(5.0, 4.0), (6.0, 3.0), (7.0, 2.0), (1.0, 5.0)].map(|(v, w)| Item {
+            value: v.into(),
+            weight: w.into(),
+        });
+        let params = WithPenalty {
+            max_weight: 8.0.into(),
+            penalty: 1.0.into(),
+        };
+        let empty = Solution::empty(&knapsack, params);
+        let greedy = ratio_greedy(&empty);
+        let mut rand = StdRng::seed_from_u64(53);
+        for a in [-0.5, 0.0, 0.5, 1.0, 1.5] {
+            for s in [
+                randomized_ratio(&empty, a, &mut rand),
+                grasp(&empty, a, &mut rand),
+            ] {
+                assert!(s.is_feasible(), "a = {a}");
+            }
+        }
+        // acima de 1, a construção pela razão é a gulosa
+        assert_eq!(randomized_ratio(&empty, 1.5, &mut rand).items, greedy.items);
+    }
+
+    #[test]
+    fn ratio_greedy_handles_weightless_items() {
+        let knapsack = [(0.0, 0.0), (-1.0, 0.0), (5.0, 4.0), (1.0, 0.0)].map(|(v, w)| Item {
+            value: v.into(),
+            weight: w.into(),
+        });
+        let params = WithPenalty {
+            max_weight: 4.0.into(),
+            penalty: 1.0.into(),
+        };
+        let empty = Solution::empty(&knapsack, params);
+        assert_eq!(empty.ratio(3), f64::INFINITY);
+        assert_eq!(empty.ratio(1), f64::NEG_INFINITY);
+        let greedy = ratio_greedy(&empty);
+        assert_eq!(
+            greedy.items.iter().by_vals().collect::<Vec<_>>(),
+            [true, false, true, true]
+        );
+    }
+}
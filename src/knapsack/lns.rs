@@ -0,0 +1,23 @@
+//! Operadores de destruição de soluções binárias para a busca em vizinhança grande. A
+//! reparação fica a cargo de [`super::repair`] e [`super::construction`].
+use rand::{seq::IteratorRandom, Rng};
+
+use super::KnapsackSolution;
+
+/// Remove `q` ítens escolhidos aleatórios.
+pub fn random_drop<S: KnapsackSolution>(s: &S, q: usize, rand: &mut impl Rng) -> S {
+    let mut s = s.clone();
+    for i in s.items().iter_ones().choose_multiple(rand, q) {
+        s.flip_in_place(i);
+    }
+    s
+}
+
+/// Remove os `q` ítens escolhidos de pior razão valor/peso.
+pub fn worst_drop<S: KnapsackSolution>(s: &S, q: usize) -> S {
+    let mut s = s.clone();
+    for i in s.by_worst_ratio().into_iter().take(q) {
+        s.flip_in_place(i);
+    }
+    s
+}
@@ -0,0 +1,502 @@
+//! Problema da mochila multidimensional 0/1: cada ítem consome uma quantidade de cada um dos
+//! `m` recursos, e todas as `m` capacidades devem ser respeitadas.
+use std::io::BufRead;
+
+use bitvec::{bitvec, vec::BitVec};
+
+use super::{value_ratio, KnapsackSolution, UWeight, Weight};
+
+#[derive(Debug, Clone)]
+pub struct Item {
+    pub value: UWeight,
+    /// Consumo de cada recurso.
+    pub weights: Vec<UWeight>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Instance {
+    pub items: Vec<Item>,
+    pub capacities: Vec<UWeight>,
+    /// Peso substituto de cada ítem: a soma de seus pesos normalizados pelas capacidades.
+    surrogate: Vec<UWeight>,
+    /// Índices dos ítens em ordem decrescente de razão valor/peso substituto.
+    order: Vec<usize>,
+}
+
+impl Instance {
+    pub fn new(items: Vec<Item>, capacities: Vec<UWeight>) -> Self {
+        let surrogate = items
+            .iter()
+            .map(|item| {
+                item.weights
+                    .iter()
+                    .zip(&capacities)
+                    .map(|(w, c)| *w / *c)
+                    .sum()
+            })
+            .collect();
+        let mut this = Self {
+            items,
+            capacities,
+            surrogate,
+            order: vec![],
+        };
+        let mut order = (0..this.items.len()).collect::<Vec<_>>();
+        order.sort_by_key(|i| std::cmp::Reverse(this.ratio(*i)));
+        this.order = order;
+        this
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.capacities.len()
+    }
+
+    pub fn surrogate_weight(&self, index: usize) -> UWeight {
+        self.surrogate[index]
+    }
+
+    /// Razão entre valor e peso substituto, usada para ordenar os ítens na reparação.
+    pub fn ratio(&self, index: usize) -> UWeight {
+        value_ratio(self.items[index].value, self.surrogate[index])
+    }
+
+    /// Índices dos ítens em ordem decrescente de razão valor/peso substituto, calculada uma
+    /// única vez na construção da instância.
+    pub fn by_ratio(&self) -> &[usize] {
+        &self.order
+    }
+}
+
+/// Lê um arquivo no formato `mknap` da OR-Library, que pode conter várias instâncias:
+///
+/// ```text
+/// K
+/// n m ótimo
+/// p1 ... pn
+/// r11 ... r1n
+/// ...
+/// rm1 ... rmn
+/// b1 ... bm
+/// ```
+pub fn read_mknap(file: &mut dyn BufRead) -> std::io::Result<Vec<Instance>> {
+    let mut nums = vec![];
+    for line in file.lines() {
+        // adiciona todos os números no vetor `nums`
+        nums.extend(
+            line?
+                .split_whitespace()
+                .map(|num| num.parse::<UWeight>().expect("Número inválido")),
+        )
+    }
+    let mut nums = nums.into_iter();
+    let mut next = || nums.next().expect("Arquivo terminou antes do esperado");
+
+    let count = next().0 as usize;
+    let mut instances = Vec::with_capacity(count);
+    for _ in 0..count {
+        let n = next().0 as usize;
+        let m = next().0 as usize;
+        // valor ótimo conhecido, ignorado
+        next();
+        let mut items = (0..n)
+            .map(|_| Item {
+                value: next(),
+                weights: Vec::with_capacity(m),
+            })
+            .collect::<Vec<_>>();
+        for _ in 0..m {
+            for item in &mut items {
+                item.weights.push(next());
+            }
+        }
+        let capacities = (0..m).map(|_| next()).collect::<Vec<_>>();
+        // o peso substituto divide pelas capacidades
+        if capacities.iter().any(|c| c.0 <= 0.0) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "As capacidades devem ser positivas",
+            ));
+        }
+        instances.push(Instance::new(items, capacities));
+    }
+    Ok(instances)
+}
+
+/// Como soluções que violam alguma capacidade são avaliadas.
+#[derive(Debug, Clone, Copy)]
+pub enum Evaluation {
+    /// Subtrai do valor total `penalty` vezes a soma dos excessos de cada recurso.
+    Penalty(UWeight),
+    /// Mantém toda solução viável: quando um ítem adicionado não cabe, remove os ítens de menor
+    /// razão valor/peso substituto (possivelmente o próprio ítem adicionado) até que a solução
+    /// volte a ser viável. A reparação não completa a solução com outros ítens, para que uma
+    /// remoção não seja desfeita imediatamente; para isso, use [`super::repair::decode`] no
+    /// incumbente.
+    SurrogateRepair,
+}
+
+#[derive(Clone)]
+pub struct Solution<'ks> {
+    pub items: BitVec,
+    pub value: Weight,
+    pub instance: &'ks Instance,
+    pub evaluation: Evaluation,
+    /// Consumo total de cada recurso, soma dos valores e soma dos pesos substitutos dos ítens
+    /// escolhidos, mantidos a cada inversão.
+    totals: Vec<UWeight>,
+    total_value: UWeight,
+    total_weight: UWeight,
+}
+
+impl<'ks> Solution<'ks> {
+    pub fn new(instance: &'ks Instance, items: BitVec, evaluation: Evaluation) -> Self {
+        let mut this = Self {
+            items,
+            value: 0.0.into(),
+            instance,
+            evaluation,
+            totals: vec![],
+            total_value: 0.0.into(),
+            total_weight: 0.0.into(),
+        };
+        this.totals = this.compute_totals();
+        this.total_value = this
+            .items
+            .iter_ones()
+            .map(|i| instance.items[i].value)
+            .sum();
+        this.total_weight = this
+            .items
+            .iter_ones()
+            .map(|i| instance.surrogate_weight(i))
+            .sum();
+        if let Evaluation::SurrogateRepair = evaluation {
+            this.repair();
+        }
+        this.value = this.evaluate();
+        this
+    }
+
+    pub fn empty(instance: &'ks Instance, evaluation: Evaluation) -> Self {
+        Self::new(instance, bitvec![0; instance.items.len()], evaluation)
+    }
+
+    /// Adiciona os ítens em ordem decrescente de razão valor/peso substituto, desde que caibam
+    /// em todas as dimensões.
+    pub fn greedy(instance: &'ks Instance, evaluation: Evaluation) -> Self {
+        let mut this = Self::empty(instance, evaluation);
+        this.fill(instance.by_ratio());
+        this.value = this.evaluate();
+        this
+    }
+
+    fn compute_totals(&self) -> Vec<UWeight> {
+        let mut totals = vec![UWeight::from(0.0); self.instance.dimensions()];
+        for index in self.items.iter_ones() {
+            for (total, w) in totals.iter_mut().zip(&self.instance.items[index].weights) {
+                *total += *w;
+            }
+        }
+        totals
+    }
+
+    fn fits(&self, index: usize) -> bool {
+        self.totals
+            .iter()
+            .zip(&self.instance.items[index].weights)
+            .zip(&self.instance.capacities)
+            .all(|((total, w), c)| *total + *w <= *c)
+    }
+
+    fn set(&mut self, index: usize, chosen: bool) {
+        if self.items[index] == chosen {
+            return;
+        }
+        self.items.set(index, chosen);
+        let value = self.instance.items[index].value;
+        let weight = self.instance.surrogate_weight(index);
+        if chosen {
+            self.total_value += value;
+            self.total_weight += weight;
+        } else {
+            self.total_value -= value;
+            self.total_weight -= weight;
+        }
+        for (total, w) in self
+            .totals
+            .iter_mut()
+            .zip(&self.instance.items[index].weights)
+        {
+            if chosen {
+                *total += *w;
+            } else {
+                *total -= *w;
+            }
+        }
+    }
+
+    // Adiciona, na ordem dada, os ítens que couberem.
+    fn fill(&mut self, order: &[usize]) {
+        for &index in order {
+            if !self.items[index] && self.fits(index) {
+                self.set(index, true);
+            }
+        }
+    }
+
+    /// Torna a solução viável removendo os ítens de pior razão valor/peso substituto.
+    pub fn repair(&mut self) {
+        let instance = self.instance;
+        for &index in instance.by_ratio().iter().rev() {
+            if self.excess() == 0.0 {
+                break;
+            }
+            if self.items[index] {
+                self.set(index, false);
+            }
+        }
+    }
+
+    /// Valor perdido pela reparação após adicionar o ítem `added`, sem modificar a solução.
+    fn repair_loss(&self, added: usize) -> UWeight {
+        let instance = self.instance;
+        let mut totals = self.totals.clone();
+        let apply = |totals: &mut [UWeight], index: usize, sign: f64| {
+            for (total, w) in totals.iter_mut().zip(&instance.items[index].weights) {
+                *total += *w * sign;
+            }
+        };
+        apply(&mut totals, added, 1.0);
+        let mut lost = UWeight::from(0.0);
+        for &index in instance.by_ratio().iter().rev() {
+            let feasible = totals.iter().zip(&instance.capacities).all(|(t, c)| t <= c);
+            if feasible {
+                break;
+            }
+            if index == added || self.items[index] {
+                apply(&mut totals, index, -1.0);
+                lost += instance.items[index].value;
+            }
+        }
+        lost
+    }
+
+    /// Soma dos excessos de capacidade de todos os recursos após inverter o ítem `index`.
+    fn flip_excess(&self, index: usize) -> UWeight {
+        let sign = if self.items[index] { -1.0 } else { 1.0 };
+        self.totals
+            .iter()
+            .zip(&self.instance.items[index].weights)
+            .zip(&self.instance.capacities)
+            .map(|((total, w), c)| (*total + *w * sign - *c).max(0.0.into()))
+            .sum()
+    }
+
+    pub fn totals(&self) -> &[UWeight] {
+        &self.totals
+    }
+
+    /// Soma dos excessos de capacidade de todos os recursos.
+    pub fn excess(&self) -> UWeight {
+        self.totals
+            .iter()
+            .zip(&self.instance.capacities)
+            .map(|(total, c)| (*total - *c).max(0.0.into()))
+            .sum()
+    }
+
+    pub fn total_value(&self) -> UWeight {
+        self.total_value
+    }
+
+    fn evaluate(&self) -> Weight {
+        match self.evaluation {
+            Evaluation::Penalty(penalty) => self.total_value() - penalty * self.excess(),
+            Evaluation::SurrogateRepair => self.total_value(),
+        }
+    }
+
+    /// Quanto a avaliação mudaria ao inverter o ítem `index`, em O(m) exceto quando uma adição
+    /// precisa ser reparada.
+    pub fn flip_delta(&self, index: usize) -> Weight {
+        let value = self.instance.items[index].value;
+        match self.evaluation {
+            Evaluation::Penalty(penalty) => {
+                let change = if self.items[index] { -value } else { value };
+                self.total_value + change - penalty * self.flip_excess(index) - self.value
+            }
+            Evaluation::SurrogateRepair if self.items[index] => -value,
+            Evaluation::SurrogateRepair if self.flip_excess(index) == 0.0 => value,
+            Evaluation::SurrogateRepair => value - self.repair_loss(index),
+        }
+    }
+
+    /// Inverte o ítem `index` na própria solução, reparando-a se a avaliação exigir.
+    pub fn flip_in_place(&mut self, index: usize) {
+        let chosen = !self.items[index];
+        self.set(index, chosen);
+        if let Evaluation::SurrogateRepair = self.evaluation {
+            self.repair();
+        }
+        self.value = self.evaluate();
+    }
+
+    pub fn flip(&self, index: usize) -> Self {
+        let mut flipped = self.clone();
+        flipped.flip_in_place(index);
+        flipped
+    }
+}
+
+impl KnapsackSolution for Solution<'_> {
+    fn item_count(&self) -> usize {
+        self.instance.items.len()
+    }
+    fn items(&self) -> &BitVec {
+        &self.items
+    }
+    fn value(&self) -> Weight {
+        self.value
+    }
+    fn total_value(&self) -> UWeight {
+        Solution::total_value(self)
+    }
+    fn total_weight(&self) -> UWeight {
+        self.total_weight
+    }
+    fn item_value(&self, index: usize) -> UWeight {
+        self.instance.items[index].value
+    }
+    fn item_weight(&self, index: usize) -> UWeight {
+        self.instance.surrogate_weight(index)
+    }
+    fn is_feasible(&self) -> bool {
+        self.excess() == 0.0
+    }
+    fn flip(&self, index: usize) -> Self {
+        Solution::flip(self, index)
+    }
+    fn flip_delta(&self, index: usize) -> Weight {
+        Solution::flip_delta(self, index)
+    }
+    /// Se a inversão cabe sem precisar de reparação.
+    fn flip_is_feasible(&self, index: usize) -> bool {
+        self.flip_excess(index) == 0.0
+    }
+    fn flip_in_place(&mut self, index: usize) {
+        Solution::flip_in_place(self, index)
+    }
+    fn empty(&self) -> Self {
+        Self::empty(self.instance, self.evaluation)
+    }
+    fn by_worst_ratio(&self) -> Vec<usize> {
+        let order = self.instance.by_ratio();
+        order
+            .iter()
+            .rev()
+            .copied()
+            .filter(|i| self.items[*i])
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for Solution<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:b}", self.items)
+    }
+}
+
+impl std::cmp::PartialEq for Solution<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value.eq(&other.value)
+    }
+}
+
+impl std::cmp::PartialOrd for Solution<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    const MKNAP: &str = "1\n3 2 0\n10 20 30\n1 2 3\n4 5\n6\n7 8\n";
+
+    #[test]
+    fn reads_mknap() {
+        let instances = read_mknap(&mut MKNAP.as_bytes()).unwrap();
+        assert_eq!(instances.len(), 1);
+        let instance = &instances[0];
+        assert_eq!(instance.capacities, [UWeight::from(7.0), 8.0.into()]);
+        let items = instance
+            .items
+            .iter()
+            .map(|item| (item.value.0, item.weights.iter().map(|w| w.0).collect()))
+            .collect::<Vec<(f64, Vec<f64>)>>();
+        assert_eq!(
+            items,
+            [
+                (10.0, vec![1.0, 4.0]),
+                (20.0, vec![2.0, 5.0]),
+                (30.0, vec![3.0, 6.0])
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_zero_capacity() {
+        let input = "1\n3 2 0\n10 20 30\n1 2 3\n4 5\n6\n7 0\n";
+        assert!(read_mknap(&mut input.as_bytes()).is_err());
+    }
+
+    fn random_instance(rand: &mut StdRng) -> Instance {
+        let items = (0..8)
+            .map(|_| Item {
+                value: (rand.gen_range(1..50) as f64).into(),
+                weights: (0..3)
+                    .map(|_| (rand.gen_range(1..20) as f64).into())
+                    .collect(),
+            })
+            .collect();
+        Instance::new(items, vec![40.0.into(); 3])
+    }
+
+    #[test]
+    fn flip_delta_matches_evaluation() {
+        let mut rand = StdRng::seed_from_u64(11);
+        for _ in 0..50 {
+            let instance = random_instance(&mut rand);
+            let items = (0..8).map(|_| rand.gen_bool(0.5)).collect::<BitVec>();
+            for evaluation in [Evaluation::Penalty(2.0.into()), Evaluation::SurrogateRepair] {
+                let s = Solution::new(&instance, items.clone(), evaluation);
+                for i in 0..8 {
+                    let s_prime = s.flip(i);
+                    assert_eq!(s.flip_delta(i), s_prime.value - s.value, "{evaluation:?}");
+                    // as somas mantidas a cada inversão batem com as recalculadas
+                    let fresh = Solution::new(&instance, s_prime.items.clone(), evaluation);
+                    assert_eq!(s_prime.value, fresh.value);
+                    assert_eq!(s_prime.totals(), fresh.totals());
+                    assert!((s_prime.total_weight() - fresh.total_weight()).abs() < 1e-9);
+                    if let Evaluation::SurrogateRepair = evaluation {
+                        assert!(s_prime.is_feasible());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn repair_removes_only() {
+        let mut rand = StdRng::seed_from_u64(13);
+        let instance = random_instance(&mut rand);
+        let s = Solution::greedy(&instance, Evaluation::SurrogateRepair);
+        for i in s.items.iter_ones() {
+            // remover um ítem não o adiciona de volta
+            assert!(!s.flip(i).items[i]);
+        }
+    }
+}
@@ -0,0 +1,396 @@
+//! Problema das múltiplas mochilas 0/1: há `m` mochilas, cada uma com sua capacidade, e cada
+//! ítem pode ser colocado em no máximo uma delas.
+use std::io::BufRead;
+
+use rand::Rng;
+
+use super::{EvaluationMethod, Item, UWeight, Weight, WithPenalty};
+
+/// Lê uma instância no formato
+///
+/// ```text
+/// n m
+/// c1 ... cm
+/// v1 w1
+/// ...
+/// vn wn
+/// ```
+///
+/// devolvendo as capacidades das mochilas e os ítens.
+pub fn read_multiple_knapsack(
+    file: &mut dyn BufRead,
+) -> std::io::Result<(Vec<UWeight>, Vec<Item>)> {
+    let mut nums = vec![];
+    for line in file.lines() {
+        // adiciona todos os números no vetor `nums`
+        nums.extend(
+            line?
+                .split_whitespace()
+                .map(|num| num.parse::<UWeight>().expect("Número inválido")),
+        )
+    }
+
+    // Remove primeira linha
+    let info = nums.drain(0..=1).collect::<Vec<_>>();
+    let m = info[1].0 as usize;
+    let capacities = nums.drain(0..m).collect();
+    Ok((
+        capacities,
+        nums.chunks_exact(2)
+            .map(|pair| Item {
+                value: pair[0],
+                weight: pair[1],
+            })
+            .collect(),
+    ))
+}
+
+/// Um [`WithPenalty`] por mochila, cada um com sua capacidade: a solução perde `penalty` vezes a
+/// soma dos excessos de todas as mochilas. Com uma só mochila, é a avaliação usual.
+pub fn with_penalty(capacities: &[UWeight], penalty: UWeight) -> Vec<WithPenalty> {
+    capacities
+        .iter()
+        .map(|&max_weight| WithPenalty {
+            max_weight,
+            penalty,
+        })
+        .collect()
+}
+
+/// Movimentos da vizinhança das múltiplas mochilas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    /// Coloca o ítem na mochila indicada, ou o retira se `None`.
+    Assign(usize, Option<usize>),
+    /// Troca as mochilas de dois ítens.
+    Swap(usize, usize),
+}
+
+impl Move {
+    /// Ítens alterados pelo movimento.
+    pub fn items(self) -> impl Iterator<Item = usize> {
+        let (a, b) = match self {
+            Move::Assign(i, _) => (i, None),
+            Move::Swap(i, j) => (i, Some(j)),
+        };
+        std::iter::once(a).chain(b)
+    }
+}
+
+/// Solução representada pela mochila de cada ítem. Cada mochila tem seu próprio
+/// [`EvaluationMethod`], cuja capacidade é [`EvaluationMethod::max_weight`], e a avaliação da
+/// solução é a soma das avaliações das mochilas. Como ela é separável, um movimento é avaliado
+/// olhando só as mochilas que altera.
+#[derive(Clone)]
+pub struct Solution<'ks, E: EvaluationMethod = WithPenalty> {
+    pub bins: Vec<Option<usize>>,
+    pub value: Weight,
+    pub knapsack: &'ks [Item],
+    /// Avaliação de cada mochila.
+    pub eval_methods: &'ks [E],
+    /// Soma dos pesos de cada mochila.
+    loads: Vec<UWeight>,
+    /// Soma dos valores de cada mochila.
+    values: Vec<UWeight>,
+}
+
+impl<'ks, E> Solution<'ks, E>
+where
+    E: EvaluationMethod,
+{
+    pub fn new(knapsack: &'ks [Item], bins: Vec<Option<usize>>, eval_methods: &'ks [E]) -> Self {
+        let mut loads = vec![UWeight::from(0.0); eval_methods.len()];
+        let mut values = loads.clone();
+        for (item, bin) in knapsack.iter().zip(&bins) {
+            if let Some(bin) = *bin {
+                loads[bin] += item.weight;
+                values[bin] += item.value;
+            }
+        }
+        let mut this = Self {
+            bins,
+            value: 0.0.into(),
+            knapsack,
+            eval_methods,
+            loads,
+            values,
+        };
+        this.value = (0..this.bin_count())
+            .map(|bin| this.evaluate_bin(bin))
+            .sum();
+        this
+    }
+    pub fn empty(knapsack: &'ks [Item], eval_methods: &'ks [E]) -> Self {
+        Self::new(knapsack, vec![None; knapsack.len()], eval_methods)
+    }
+    /// Adiciona os ítens de valor não negativo em ordem decrescente de razão valor/peso, cada um
+    /// na primeira mochila, da menor para a maior, em que couber. Ítens sem peso vêm primeiro.
+    pub fn greedy(knapsack: &'ks [Item], eval_methods: &'ks [E]) -> Self {
+        let mut items = (0..knapsack.len())
+            .filter(|i| knapsack[*i].value >= 0.0.into())
+            .collect::<Vec<_>>();
+        items.sort_by_key(|i| {
+            let Item { value, weight } = knapsack[*i];
+            let ratio = if weight == 0.0 {
+                f64::INFINITY.into()
+            } else {
+                value / weight
+            };
+            std::cmp::Reverse(ratio)
+        });
+        let mut order = (0..eval_methods.len()).collect::<Vec<_>>();
+        order.sort_by_key(|b| eval_methods[*b].max_weight());
+
+        let mut remaining = eval_methods
+            .iter()
+            .map(|e| e.max_weight())
+            .collect::<Vec<_>>();
+        let mut bins = vec![None; knapsack.len()];
+        for i in items {
+            let weight = knapsack[i].weight;
+            if let Some(&bin) = order.iter().find(|b| remaining[**b] >= weight) {
+                remaining[bin] -= weight;
+                bins[i] = Some(bin);
+            }
+        }
+        Self::new(knapsack, bins, eval_methods)
+    }
+    pub fn bin_count(&self) -> usize {
+        self.loads.len()
+    }
+    pub fn total_value(&self) -> UWeight {
+        self.values.iter().copied().sum()
+    }
+    /// Soma dos pesos de cada mochila.
+    pub fn loads(&self) -> &[UWeight] {
+        &self.loads
+    }
+    pub fn is_feasible(&self) -> bool {
+        self.loads
+            .iter()
+            .zip(self.eval_methods)
+            .all(|(load, e)| *load <= e.max_weight())
+    }
+    fn evaluate_bin(&self, bin: usize) -> Weight {
+        self.eval_methods[bin].evaluate(self.values[bin], self.loads[bin])
+    }
+    fn set_bin(&mut self, index: usize, bin: Option<usize>) {
+        let item = self.knapsack[index];
+        if let Some(old) = self.bins[index] {
+            self.loads[old] -= item.weight;
+            self.values[old] -= item.value;
+        }
+        if let Some(new) = bin {
+            self.loads[new] += item.weight;
+            self.values[new] += item.value;
+        }
+        self.bins[index] = bin;
+    }
+    /// Nova mochila de cada ítem alterado pelo movimento.
+    fn transfers(&self, mv: Move) -> impl Iterator<Item = (usize, Option<usize>)> {
+        let (first, second) = match mv {
+            Move::Assign(i, bin) => ((i, bin), None),
+            Move::Swap(i, j) => ((i, self.bins[j]), Some((j, self.bins[i]))),
+        };
+        std::iter::once(first).chain(second)
+    }
+    /// Variação de (valor, peso) de cada mochila alterada pelo movimento, que são no máximo
+    /// duas.
+    fn bin_changes(&self, mv: Move) -> Vec<(usize, UWeight, UWeight)> {
+        let mut changes: Vec<(usize, UWeight, UWeight)> = Vec::with_capacity(2);
+        for (i, to) in self.transfers(mv) {
+            let item = self.knapsack[i];
+            for (bin, sign) in [(self.bins[i], -1.0), (to, 1.0)] {
+                let Some(bin) = bin else { continue };
+                let (value, weight) = (item.value * sign, item.weight * sign);
+                match changes.iter_mut().find(|(b, _, _)| *b == bin) {
+                    Some((_, v, w)) => {
+                        *v += value;
+                        *w += weight;
+                    }
+                    None => changes.push((bin, value, weight)),
+                }
+            }
+        }
+        changes
+    }
+    /// Quanto a avaliação mudaria ao aplicar o movimento. Só as mochilas envolvidas são
+    /// reavaliadas, então o custo é O(1).
+    pub fn move_delta(&self, mv: Move) -> Weight {
+        self.bin_changes(mv)
+            .into_iter()
+            .map(|(bin, value, weight)| {
+                let (old_value, old_load) = (self.values[bin], self.loads[bin]);
+                self.eval_methods[bin].evaluate(old_value + value, old_load + weight)
+                    - self.evaluate_bin(bin)
+            })
+            .sum()
+    }
+    /// Indica se a solução seria viável após aplicar o movimento.
+    pub fn move_is_feasible(&self, mv: Move) -> bool {
+        let mut loads = self.loads.clone();
+        for (bin, _, weight) in self.bin_changes(mv) {
+            loads[bin] += weight;
+        }
+        loads
+            .iter()
+            .zip(self.eval_methods)
+            .all(|(load, e)| *load <= e.max_weight())
+    }
+    /// Aplica o movimento na própria solução, em O(1).
+    pub fn apply_in_place(&mut self, mv: Move) {
+        self.value += self.move_delta(mv);
+        for (i, bin) in self.transfers(mv).collect::<Vec<_>>() {
+            self.set_bin(i, bin);
+        }
+    }
+    /// Solução vizinha obtida aplicando o movimento em uma cópia, em O(n). Prefira
+    /// [`Solution::move_delta`] para avaliar vizinhos.
+    pub fn apply(&self, mv: Move) -> Self {
+        let mut new = self.clone();
+        new.apply_in_place(mv);
+        new
+    }
+    /// Todos os movimentos que alteram a solução: cada ítem para cada outra mochila (ou para
+    /// fora), e trocas entre ítens em mochilas diferentes.
+    pub fn moves(&self) -> impl Iterator<Item = Move> + '_ {
+        let n = self.knapsack.len();
+        let assigns = (0..n).flat_map(move |i| {
+            std::iter::once(None)
+                .chain((0..self.bin_count()).map(Some))
+                .filter(move |bin| *bin != self.bins[i])
+                .map(move |bin| Move::Assign(i, bin))
+        });
+        let swaps = (0..n).flat_map(move |i| {
+            (i + 1..n)
+                .filter(move |j| self.bins[i] != self.bins[*j])
+                .map(move |j| Move::Swap(i, j))
+        });
+        assigns.chain(swaps)
+    }
+    /// Move um ítem aleatório para outra mochila (ou para fora), ou troca as mochilas de dois
+    /// ítens aleatórios. Sem ítens ou sem mochilas, nenhum movimento altera a solução e devolve
+    /// `None`.
+    pub fn random_move(&self, rand: &mut impl Rng) -> Option<Move> {
+        let n = self.knapsack.len();
+        if n == 0 || self.bin_count() == 0 {
+            return None;
+        }
+        loop {
+            let i = rand.gen_range(0..n);
+            let mv = if rand.gen() {
+                let bin = rand.gen_range(0..=self.bin_count());
+                Move::Assign(i, (bin < self.bin_count()).then_some(bin))
+            } else {
+                Move::Swap(i, rand.gen_range(0..n))
+            };
+            // descarta movimentos que não alteram a solução
+            let changes = match mv {
+                Move::Assign(i, bin) => self.bins[i] != bin,
+                Move::Swap(i, j) => self.bins[i] != self.bins[j],
+            };
+            if changes {
+                return Some(mv);
+            }
+        }
+    }
+}
+
+impl<E: EvaluationMethod> std::fmt::Debug for Solution<'_, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.bins)
+    }
+}
+
+impl<E: EvaluationMethod> std::cmp::PartialEq for Solution<'_, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value.eq(&other.value)
+    }
+}
+
+impl<E: EvaluationMethod> std::cmp::PartialOrd for Solution<'_, E> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn reads_multiple_knapsack() {
+        let input = "3 2\n10 20\n5 4\n6 3\n7 2\n";
+        let (capacities, items) = read_multiple_knapsack(&mut input.as_bytes()).unwrap();
+        assert_eq!(capacities, [UWeight::from(10.0), 20.0.into()]);
+        assert_eq!(items.len(), 3);
+        assert_eq!((items[2].value.0, items[2].weight.0), (7.0, 2.0));
+    }
+
+    #[test]
+    fn moves_match_evaluation() {
+        let mut rand = StdRng::seed_from_u64(23);
+        for _ in 0..50 {
+            let knapsack = (0..8)
+                .map(|_| Item {
+                    value: (rand.gen_range(1..30) as f64).into(),
+                    weight: (rand.gen_range(1..10) as f64).into(),
+                })
+                .collect::<Vec<_>>();
+            let capacities = (0..3)
+                .map(|_| (rand.gen_range(5..20) as f64).into())
+                .collect::<Vec<_>>();
+            let eval_methods = with_penalty(&capacities, 2.0.into());
+            let bins = (0..knapsack.len())
+                .map(|_| rand.gen_bool(0.7).then(|| rand.gen_range(0..3)))
+                .collect();
+            let mut s = Solution::new(&knapsack, bins, &eval_methods);
+            for _ in 0..20 {
+                let mv = s.random_move(&mut rand).unwrap();
+                let delta = s.move_delta(mv);
+                let feasible = s.move_is_feasible(mv);
+                let before = s.value;
+                s.apply_in_place(mv);
+
+                // reavalia do zero
+                let fresh = Solution::new(&knapsack, s.bins.clone(), &eval_methods);
+                assert_eq!(before + delta, fresh.value, "{mv:?}");
+                assert_eq!(s.value, fresh.value, "{mv:?}");
+                assert_eq!(s.loads(), fresh.loads());
+                assert_eq!(feasible, fresh.is_feasible(), "{mv:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn random_move_without_items_or_bins() {
+        let knapsack = [Item {
+            value: 1.0.into(),
+            weight: 1.0.into(),
+        }];
+        let mut rand = StdRng::seed_from_u64(43);
+        let none = with_penalty(&[], 1.0.into());
+        assert!(Solution::empty(&knapsack, &none)
+            .random_move(&mut rand)
+            .is_none());
+        let one = with_penalty(&[UWeight::from(5.0)], 1.0.into());
+        assert!(Solution::empty(&[], &one).random_move(&mut rand).is_none());
+        assert!(Solution::empty(&knapsack, &one)
+            .random_move(&mut rand)
+            .is_some());
+    }
+
+    #[test]
+    fn greedy_takes_weightless_items() {
+        let knapsack = [(3.0, 0.0), (-1.0, 0.0), (4.0, 2.0), (0.0, 0.0)].map(|(v, w)| Item {
+            value: UWeight::from(v),
+            weight: UWeight::from(w),
+        });
+        let eval_methods = with_penalty(&[UWeight::from(2.0)], 1.0.into());
+        let s = Solution::greedy(&knapsack, &eval_methods);
+        assert_eq!(s.bins, [Some(0), None, Some(0), Some(0)]);
+        assert_eq!(s.total_value(), 7.0);
+    }
+}
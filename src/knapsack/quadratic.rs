@@ -0,0 +1,358 @@
+//! Problema da mochila quadrática 0/1: além do lucro de cada ítem, cada par de ítens escolhidos
+//! contribui com um lucro adicional dado por uma matriz de lucros.
+use std::io::BufRead;
+
+use bitvec::{bitvec, vec::BitVec};
+
+use super::{value_ratio, EvaluationMethod, KnapsackSolution, UWeight, Weight, WithPenalty};
+
+#[derive(Debug, Clone)]
+pub struct Instance {
+    /// Matriz simétrica de lucros `n × n`; a diagonal contém o lucro de cada ítem.
+    profits: Vec<UWeight>,
+    pub weights: Vec<UWeight>,
+    pub capacity: UWeight,
+}
+
+impl Instance {
+    pub fn new(profits: Vec<UWeight>, weights: Vec<UWeight>, capacity: UWeight) -> Self {
+        assert_eq!(profits.len(), weights.len() * weights.len());
+        Self {
+            profits,
+            weights,
+            capacity,
+        }
+    }
+    pub fn item_count(&self) -> usize {
+        self.weights.len()
+    }
+    /// Lucro do par `(i, j)`, ou do próprio ítem se `i == j`.
+    pub fn profit(&self, i: usize, j: usize) -> UWeight {
+        self.profits[i * self.item_count() + j]
+    }
+    fn row(&self, i: usize) -> &[UWeight] {
+        let n = self.item_count();
+        &self.profits[i * n..(i + 1) * n]
+    }
+}
+
+/// Lê uma instância no formato usual do problema (Billionnet e Soutif):
+///
+/// ```text
+/// nome
+/// n
+/// p11 ... pnn        (lucros dos ítens)
+/// p12 ... p1n        (triângulo superior da matriz, uma linha por ítem)
+/// ...
+/// p(n-1)n
+///
+/// 0
+/// capacidade
+/// w1 ... wn
+/// ```
+pub fn read_qkp(file: &mut dyn BufRead) -> std::io::Result<Instance> {
+    let mut nums = vec![];
+    // a primeira linha é o nome da instância
+    for line in file.lines().skip(1) {
+        // adiciona todos os números no vetor `nums`
+        nums.extend(
+            line?
+                .split_whitespace()
+                .map(|num| num.parse::<UWeight>().expect("Número inválido")),
+        )
+    }
+    let mut nums = nums.into_iter();
+    let mut next = || nums.next().expect("Arquivo terminou antes do esperado");
+
+    let n = next().0 as usize;
+    let mut profits = vec![UWeight::from(0.0); n * n];
+    for i in 0..n {
+        profits[i * n + i] = next();
+    }
+    for i in 0..n {
+        for j in i + 1..n {
+            let p = next();
+            profits[i * n + j] = p;
+            profits[j * n + i] = p;
+        }
+    }
+    // tipo da restrição, sempre 0 (≤)
+    next();
+    let capacity = next();
+    let weights = (0..n).map(|_| next()).collect();
+    Ok(Instance::new(profits, weights, capacity))
+}
+
+/// Solução binária que mantém, para cada ítem, sua contribuição ao valor total caso esteja na
+/// solução: seu lucro mais os lucros dos pares com os ítens escolhidos. Assim a variação do
+/// valor ao inverter um ítem é conhecida em O(1), e aplicar a inversão custa O(n).
+#[derive(Clone)]
+pub struct Solution<'ks, E: EvaluationMethod = WithPenalty> {
+    pub items: BitVec,
+    pub value: Weight,
+    pub instance: &'ks Instance,
+    pub eval_method: E,
+    total_value: UWeight,
+    total_weight: UWeight,
+    contributions: Vec<UWeight>,
+}
+
+impl<'ks, E> Solution<'ks, E>
+where
+    E: EvaluationMethod,
+{
+    pub fn new(instance: &'ks Instance, items: BitVec, eval_method: E) -> Self {
+        let n = instance.item_count();
+        let contributions = (0..n)
+            .map(|i| {
+                instance.profit(i, i)
+                    + items
+                        .iter_ones()
+                        .filter(|j| *j != i)
+                        .map(|j| instance.profit(i, j))
+                        .sum::<UWeight>()
+            })
+            .collect::<Vec<_>>();
+        // cada par é contado duas vezes nas contribuições
+        let doubled: UWeight = items
+            .iter_ones()
+            .map(|i| contributions[i] + instance.profit(i, i))
+            .sum();
+        let total_value = doubled / 2.0;
+        let total_weight = items.iter_ones().map(|i| instance.weights[i]).sum();
+        Self {
+            value: eval_method.evaluate(total_value, total_weight),
+            items,
+            instance,
+            eval_method,
+            total_value,
+            total_weight,
+            contributions,
+        }
+    }
+    pub fn empty(instance: &'ks Instance, eval_method: E) -> Self {
+        Self::new(instance, bitvec![0; instance.item_count()], eval_method)
+    }
+    /// Adiciona repetidamente o ítem de maior contribuição por peso que ainda cabe. Ítens sem
+    /// peso são levados antes dos outros se a contribuição não for negativa, e nunca se for.
+    pub fn greedy(instance: &'ks Instance, eval_method: E) -> Self {
+        let mut this = Self::empty(instance, eval_method);
+        loop {
+            let best = this
+                .items
+                .iter_zeros()
+                .filter(|i| this.total_weight + instance.weights[*i] <= eval_method.max_weight())
+                .filter(|i| instance.weights[*i] != 0.0 || this.contributions[*i].0 >= 0.0)
+                .max_by_key(|i| value_ratio(this.contributions[*i], instance.weights[*i]));
+            match best {
+                Some(i) => this.flip_in_place(i),
+                None => break this,
+            }
+        }
+    }
+    pub fn total_value(&self) -> UWeight {
+        self.total_value
+    }
+    pub fn total_weight(&self) -> UWeight {
+        self.total_weight
+    }
+    /// Quanto o valor total mudaria ao inverter o ítem `index`.
+    pub fn flip_value_delta(&self, index: usize) -> UWeight {
+        if self.items[index] {
+            -self.contributions[index]
+        } else {
+            self.contributions[index]
+        }
+    }
+    /// Quanto a avaliação mudaria ao inverter o ítem `index`.
+    pub fn flip_delta(&self, index: usize) -> Weight {
+        let weight = if self.items[index] {
+            -self.instance.weights[index]
+        } else {
+            self.instance.weights[index]
+        };
+        self.eval_method.evaluate(
+            self.total_value + self.flip_value_delta(index),
+            self.total_weight + weight,
+        ) - self.value
+    }
+    /// Inverte o ítem `index`, atualizando as contribuições dos outros ítens em O(n).
+    pub fn flip_in_place(&mut self, index: usize) {
+        let chosen = !self.items[index];
+        self.total_value += self.flip_value_delta(index);
+        let (weight, row) = (self.instance.weights[index], self.instance.row(index));
+        if chosen {
+            self.total_weight += weight;
+        } else {
+            self.total_weight -= weight;
+        }
+        for (j, (contribution, p)) in self.contributions.iter_mut().zip(row).enumerate() {
+            if j == index {
+                continue;
+            }
+            if chosen {
+                *contribution += *p;
+            } else {
+                *contribution -= *p;
+            }
+        }
+        self.items.set(index, chosen);
+        self.value = self
+            .eval_method
+            .evaluate(self.total_value, self.total_weight);
+    }
+    pub fn flip(&self, index: usize) -> Self {
+        let mut flipped = self.clone();
+        flipped.flip_in_place(index);
+        flipped
+    }
+}
+
+impl<E: EvaluationMethod> KnapsackSolution for Solution<'_, E> {
+    fn item_count(&self) -> usize {
+        self.instance.item_count()
+    }
+    fn items(&self) -> &BitVec {
+        &self.items
+    }
+    fn value(&self) -> Weight {
+        self.value
+    }
+    fn total_value(&self) -> UWeight {
+        self.total_value
+    }
+    fn total_weight(&self) -> UWeight {
+        self.total_weight
+    }
+    fn item_value(&self, index: usize) -> UWeight {
+        self.instance.profit(index, index)
+    }
+    fn item_weight(&self, index: usize) -> UWeight {
+        self.instance.weights[index]
+    }
+    fn is_feasible(&self) -> bool {
+        self.total_weight <= self.eval_method.max_weight()
+    }
+    fn flip(&self, index: usize) -> Self {
+        Solution::flip(self, index)
+    }
+    fn flip_delta(&self, index: usize) -> Weight {
+        Solution::flip_delta(self, index)
+    }
+    fn flip_is_feasible(&self, index: usize) -> bool {
+        let weight = self.instance.weights[index];
+        if self.items[index] {
+            self.total_weight - weight <= self.eval_method.max_weight()
+        } else {
+            self.total_weight + weight <= self.eval_method.max_weight()
+        }
+    }
+    fn flip_in_place(&mut self, index: usize) {
+        Solution::flip_in_place(self, index)
+    }
+    fn adapt_penalty(&mut self) {
+        let feasible = KnapsackSolution::is_feasible(self);
+        if self.eval_method.adapt(feasible) {
+            self.value = self
+                .eval_method
+                .evaluate(self.total_value, self.total_weight);
+        }
+    }
+    fn empty(&self) -> Self {
+        Self::empty(self.instance, self.eval_method)
+    }
+}
+
+impl<E: EvaluationMethod> std::fmt::Debug for Solution<'_, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:b}", self.items)
+    }
+}
+
+impl<E: EvaluationMethod> std::cmp::PartialEq for Solution<'_, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value.eq(&other.value)
+    }
+}
+
+impl<E: EvaluationMethod> std::cmp::PartialOrd for Solution<'_, E> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn reads_qkp() {
+        let input = "teste\n3\n1 2 3\n4 5\n6\n\n0\n10\n7 8 9\n";
+        let instance = read_qkp(&mut input.as_bytes()).unwrap();
+        assert_eq!(instance.item_count(), 3);
+        assert_eq!(instance.profit(1, 1), 2.0);
+        assert_eq!(instance.profit(0, 2), 5.0);
+        assert_eq!(instance.profit(2, 0), 5.0);
+        assert_eq!(instance.profit(2, 1), 6.0);
+        assert_eq!(instance.capacity, 10.0);
+        assert_eq!(
+            instance.weights,
+            [UWeight::from(7.0), 8.0.into(), 9.0.into()]
+        );
+    }
+
+    #[test]
+    fn flip_delta_matches_evaluation() {
+        let mut rand = StdRng::seed_from_u64(29);
+        for _ in 0..50 {
+            let n = 7;
+            let mut profits = vec![UWeight::from(0.0); n * n];
+            for i in 0..n {
+                for j in i..n {
+                    let p = (rand.gen_range(0..20) as f64).into();
+                    profits[i * n + j] = p;
+                    profits[j * n + i] = p;
+                }
+            }
+            let weights = (0..n)
+                .map(|_| (rand.gen_range(1..10) as f64).into())
+                .collect();
+            let instance = Instance::new(profits, weights, 20.0.into());
+            let eval_method = WithPenalty {
+                max_weight: instance.capacity,
+                penalty: 2.0.into(),
+            };
+            let mut s = Solution::empty(&instance, eval_method);
+            for _ in 0..20 {
+                let i = rand.gen_range(0..n);
+                let delta = s.flip_delta(i);
+                let before = s.value;
+                s.flip_in_place(i);
+
+                // reavalia do zero
+                let fresh = Solution::new(&instance, s.items.clone(), eval_method);
+                assert_eq!(before + delta, fresh.value);
+                assert_eq!(s.value, fresh.value);
+                assert_eq!(s.total_value(), fresh.total_value());
+            }
+        }
+    }
+
+    #[test]
+    fn greedy_handles_weightless_items() {
+        // ítem 0 sem peso e lucrativo, ítem 1 sem peso e com lucro negativo, ítem 2 não cabe
+        let profits = [3.0, 0.0, 0.0, 0.0, -2.0, 0.0, 0.0, 0.0, 5.0].map(UWeight::from);
+        let weights = [0.0, 0.0, 10.0].map(UWeight::from).to_vec();
+        let instance = Instance::new(profits.to_vec(), weights, 5.0.into());
+        let eval_method = WithPenalty {
+            max_weight: instance.capacity,
+            penalty: 2.0.into(),
+        };
+        let s = Solution::greedy(&instance, eval_method);
+        assert!(s.items[0]);
+        assert!(!s.items[1]);
+        assert!(!s.items[2]);
+    }
+}
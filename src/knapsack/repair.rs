@@ -0,0 +1,55 @@
+//! Operadores que tornam viáveis e completam soluções binárias dos problemas da mochila, para
+//! uso como decodificador de qualquer metaheurística e como busca local.
+use super::KnapsackSolution;
+
+/// Remove os ítens de pior razão valor/peso até que a solução seja viável.
+pub fn repair<S: KnapsackSolution>(s: &mut S) {
+    for i in s.by_worst_ratio() {
+        if s.is_feasible() {
+            break;
+        }
+        s.flip_in_place(i);
+    }
+}
+
+/// Adiciona, em ordem decrescente de razão valor/peso, os ítens que couberem, exceto `except`.
+fn fill_except<S: KnapsackSolution>(s: &mut S, except: Option<usize>) {
+    let mut sorted = s.items().iter_zeros().collect::<Vec<_>>();
+    sorted.sort_by_key(|i| std::cmp::Reverse(s.ratio(*i)));
+    for i in sorted {
+        if Some(i) != except && s.flip_is_feasible(i) {
+            s.flip_in_place(i);
+        }
+    }
+}
+
+/// Adiciona, em ordem decrescente de razão valor/peso, os ítens que couberem.
+pub fn fill<S: KnapsackSolution>(s: &mut S) {
+    fill_except(s, None);
+}
+
+/// Decodifica uma solução qualquer em uma viável e maximal: repara e depois completa.
+pub fn decode<S: KnapsackSolution>(s: &S) -> S {
+    let mut decoded = s.clone();
+    repair(&mut decoded);
+    fill(&mut decoded);
+    decoded
+}
+
+/// Busca local de remoção e adição: tenta remover cada ítem escolhido, do de pior razão para o
+/// de melhor, e completar a solução com os outros ítens que couberem. Aceita a primeira melhora
+/// e recomeça, até que nenhuma remoção melhore a solução. A solução deve ser viável.
+pub fn drop_add_search<S: KnapsackSolution>(s: &mut S) {
+    'improved: loop {
+        for i in s.by_worst_ratio() {
+            let mut s_prime = s.clone();
+            s_prime.flip_in_place(i);
+            fill_except(&mut s_prime, Some(i));
+            if s_prime > *s {
+                *s = s_prime;
+                continue 'improved;
+            }
+        }
+        break;
+    }
+}
@@ -1,11 +1,32 @@
-use std::{fs::File, io::BufReader, ops::Range};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    ffi::OsString,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    ops::Range,
+    sync::{Mutex, OnceLock},
+};
 
+use bitvec::{bitvec, vec::BitVec};
+
+pub mod algorithms;
+pub mod alns;
+pub mod annealing;
+pub mod grasp;
 pub mod knapsack;
+pub mod relinking;
+pub mod tabu;
+pub mod tempering;
 pub mod tsp;
 pub mod utils;
+pub mod vns;
 
 pub type Set<T> = Vec<T>;
 
+/// Menor variação considerada uma melhora nas buscas locais, para que erros de arredondamento
+/// nos cálculos de variação não façam a busca ciclar.
+pub(crate) const IMPROVEMENT: f64 = 1e-9;
+
 /// Nós são identificados pelo tipo `u32`, que é um inteiro de 32 bits positivo.
 ///
 /// Equivale a um typedef em C++.
@@ -13,10 +34,19 @@ pub type Node = u32;
 /// Definimos pesos das arestas como sendo float de 32bits.
 pub type Weight = ordered_float::OrderedFloat<f64>;
 /// Definimos nossas arestas como sendo uma tupla de dois nós e um peso.
+///
+/// Comparações entre arestas ignoram o peso e a orientação. Em grafos dirigidos, `Edge(a, b, w)`
+/// representa o arco de `a` para `b`: use [`Edge::arc`] para compará-los como [`Arc`]s, levando
+/// em conta a orientação.
 #[derive(Clone, Copy, Default, Debug)]
 pub struct Edge(pub Node, pub Node, pub Weight);
 
 impl Edge {
+    /// A aresta vista como um arco de `self.0` para `self.1`.
+    pub fn arc(self) -> Arc {
+        let Edge(a, b, w) = self;
+        Arc(a, b, w)
+    }
     fn as_edge(self) -> (Node, Node) {
         if self.1 > self.0 {
             (self.1, self.0)
@@ -36,7 +66,7 @@ impl Eq for Edge {}
 
 impl PartialOrd for Edge {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.as_edge().partial_cmp(&other.as_edge())
+        Some(self.cmp(other))
     }
 }
 
@@ -46,33 +76,287 @@ impl Ord for Edge {
     }
 }
 
-/// Um `trait` que define os métodos que todo grafo deve implementar.
+/// Um arco de um grafo dirigido: origem, destino e peso.
 ///
-/// `Trait`s são análogos a classes abstratas em C++, ou interfaces em outras linguagens
-pub trait Graph {
-    fn add_nodes(&mut self, node_count: usize) -> Vec<Node> {
-        (0..node_count)
-            .into_iter()
-            .map(|_| self.add_node())
-            .collect()
+/// Ao contrário de [`Edge`], comparações levam em conta a orientação, então `Arc(a, b, w)` e
+/// `Arc(b, a, w)` são diferentes. O peso continua sendo ignorado.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Arc(pub Node, pub Node, pub Weight);
+
+impl Arc {
+    /// Origem e destino do arco, nessa ordem.
+    pub fn ends(self) -> (Node, Node) {
+        (self.0, self.1)
     }
-    fn add_node(&mut self) -> Node;
-    fn add_edge(&mut self, a: Node, b: Node, weight: Weight);
-    fn edges(&self) -> Set<Edge>;
+}
+
+impl From<Arc> for Edge {
+    fn from(Arc(a, b, w): Arc) -> Self {
+        Edge(a, b, w)
+    }
+}
+
+impl PartialEq for Arc {
+    fn eq(&self, other: &Self) -> bool {
+        self.ends().eq(&other.ends())
+    }
+}
+
+impl Eq for Arc {}
+
+impl PartialOrd for Arc {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Arc {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.ends().cmp(&other.ends())
+    }
+}
+
+impl std::hash::Hash for Arc {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.ends().hash(state);
+    }
+}
+
+/// Um `trait` que define os métodos de consulta que todo grafo deve implementar.
+///
+/// `Trait`s são análogos a classes abstratas em C++, ou interfaces em outras linguagens
+///
+/// Grafos são `Sync` para que soluções que os referenciam possam ser usadas em várias threads.
+/// A construção e a modificação ficam em [`GraphMut`], que grafos implícitos como
+/// [`CoordGraph`] não implementam.
+pub trait Graph: Sync + AsGraph {
     fn node_count(&self) -> usize;
 
-    //
-    fn get_node_edges(&self, a: Node) -> Set<Edge>;
+    /// Arestas que saem de `a`, sem alocar um vetor.
+    fn incident_edges(&self, a: Node) -> IncidentEdges<'_>;
+
+    /// Nós vizinhos de `a`, isto é, os destinos de suas arestas.
+    fn neighbours(&self, a: Node) -> Neighbours<'_> {
+        self.incident_edges(a).map(|Edge(_, b, _)| b)
+    }
+    /// Cada aresta do grafo uma única vez, sem laços. Em grafos não dirigidos, a aresta entre
+    /// `a` e `b` aparece apenas como `Edge(a, b, w)` com `a < b`.
+    fn unique_edges(&self) -> UniqueEdges<'_> {
+        UniqueEdges {
+            graph: self.as_graph(),
+            nodes: self.nodes(),
+            current: None,
+        }
+    }
+    /// Todas as arestas, incluindo laços. Em grafos não dirigidos cada aresta aparece duas
+    /// vezes, uma em cada sentido: veja [`Graph::unique_edges`].
+    fn edges(&self) -> Set<Edge> {
+        self.nodes().flat_map(|a| self.incident_edges(a)).collect()
+    }
+    fn get_node_edges(&self, a: Node) -> Set<Edge> {
+        self.incident_edges(a).collect()
+    }
+    /// Referência ao peso armazenado da aresta. Grafos implícitos, que calculam os pesos sob
+    /// demanda, retornam `None`: prefira [`Graph::get_edge_weight`].
     fn get_edge_weight_ref(&self, a: Node, b: Node) -> Option<&Weight>;
-    fn get_edge_weight_mut(&mut self, a: Node, b: Node) -> Option<&mut Weight>;
     fn get_edge_weight(&self, a: Node, b: Node) -> Option<Weight> {
         self.get_edge_weight_ref(a, b).copied()
     }
+    /// Indica se as arestas têm orientação, isto é, se o peso de `(a, b)` pode diferir do peso
+    /// de `(b, a)`.
+    fn is_directed(&self) -> bool {
+        false
+    }
     fn nodes(&self) -> Range<Node> {
         0..(self.node_count() as Node)
     }
 }
 
+/// Grafos que armazenam suas arestas e podem ser construídos e modificados.
+pub trait GraphMut: Graph {
+    fn add_nodes(&mut self, node_count: usize) -> Vec<Node> {
+        (0..node_count).map(|_| self.add_node()).collect()
+    }
+    fn add_node(&mut self) -> Node;
+    fn add_edge(&mut self, a: Node, b: Node, weight: Weight);
+    /// Pesos armazenados de todas as arestas, para modificação em lote. Em grafos não
+    /// dirigidos, cada aresta aparece uma vez em cada sentido.
+    fn weights_mut(&mut self) -> WeightsMut<'_>;
+    fn get_edge_weight_mut(&mut self, a: Node, b: Node) -> Option<&mut Weight>;
+}
+
+/// Permite que os métodos padrão de [`Graph`] obtenham `&dyn Graph` a partir de `&self`.
+/// Implementado automaticamente para todo grafo.
+pub trait AsGraph {
+    fn as_graph(&self) -> &dyn Graph;
+}
+
+impl<G: Graph> AsGraph for G {
+    fn as_graph(&self) -> &dyn Graph {
+        self
+    }
+}
+
+/// Iterador das arestas que saem de um nó, retornado por [`Graph::incident_edges`].
+///
+/// Grafos que armazenam os pesos percorrem uma linha da matriz ou uma lista de adjacência; grafos
+/// implícitos usam [`IncidentEdges::computed`], que consulta [`Graph::get_edge_weight`].
+pub struct IncidentEdges<'g>(IncidentEdgesKind<'g>);
+
+enum IncidentEdgesKind<'g> {
+    Row {
+        a: Node,
+        ones: bitvec::slice::IterOnes<'g, usize, bitvec::order::Lsb0>,
+        links: &'g [Weight],
+    },
+    List {
+        a: Node,
+        list: std::slice::Iter<'g, (Node, Weight)>,
+    },
+    Computed {
+        a: Node,
+        graph: &'g dyn Graph,
+        others: Range<Node>,
+    },
+}
+
+impl<'g> IncidentEdges<'g> {
+    /// Arestas de `a` em uma linha de uma matriz de adjacência: `present` indica quais colunas
+    /// têm aresta e `links` guarda o peso de cada coluna.
+    pub fn row(a: Node, present: &'g bitvec::slice::BitSlice, links: &'g [Weight]) -> Self {
+        Self(IncidentEdgesKind::Row {
+            a,
+            ones: present.iter_ones(),
+            links,
+        })
+    }
+
+    /// Arestas de `a` em uma lista de adjacência de pares `(destino, peso)`.
+    pub fn list(a: Node, list: &'g [(Node, Weight)]) -> Self {
+        Self(IncidentEdgesKind::List {
+            a,
+            list: list.iter(),
+        })
+    }
+
+    /// Arestas de `a` para todos os outros nós do grafo que tenham peso, calculado sob demanda.
+    pub fn computed(a: Node, graph: &'g dyn Graph) -> Self {
+        Self(IncidentEdgesKind::Computed {
+            a,
+            graph,
+            others: graph.nodes(),
+        })
+    }
+}
+
+impl Iterator for IncidentEdges<'_> {
+    type Item = Edge;
+
+    fn next(&mut self) -> Option<Edge> {
+        match &mut self.0 {
+            IncidentEdgesKind::Row { a, ones, links } => {
+                ones.next().map(|b| Edge(*a, b as Node, links[b]))
+            }
+            IncidentEdgesKind::List { a, list } => list.next().map(|&(b, w)| Edge(*a, b, w)),
+            IncidentEdgesKind::Computed { a, graph, others } => others
+                .filter(|b| b != a)
+                .find_map(|b| Some(Edge(*a, b, graph.get_edge_weight(*a, b)?))),
+        }
+    }
+}
+
+/// Iterador dos vizinhos de um nó, retornado por [`Graph::neighbours`].
+pub type Neighbours<'g> = std::iter::Map<IncidentEdges<'g>, fn(Edge) -> Node>;
+
+/// Iterador retornado por [`Graph::unique_edges`].
+pub struct UniqueEdges<'g> {
+    graph: &'g dyn Graph,
+    nodes: Range<Node>,
+    current: Option<IncidentEdges<'g>>,
+}
+
+impl Iterator for UniqueEdges<'_> {
+    type Item = Edge;
+
+    fn next(&mut self) -> Option<Edge> {
+        let directed = self.graph.is_directed();
+        loop {
+            if let Some(edges) = &mut self.current {
+                let unique = edges.find(|Edge(a, b, _)| if directed { a != b } else { a < b });
+                if unique.is_some() {
+                    return unique;
+                }
+            }
+            self.current = Some(self.graph.incident_edges(self.nodes.next()?));
+        }
+    }
+}
+
+/// Iterador dos pesos armazenados, retornado por [`GraphMut::weights_mut`].
+pub struct WeightsMut<'g>(WeightsMutKind<'g>);
+
+enum WeightsMutKind<'g> {
+    Matrix {
+        node_count: usize,
+        links: std::iter::Enumerate<std::slice::IterMut<'g, Weight>>,
+        present: &'g bitvec::slice::BitSlice,
+    },
+    Lists {
+        rows: std::iter::Enumerate<std::slice::IterMut<'g, Vec<(Node, Weight)>>>,
+        current: Option<(Node, std::slice::IterMut<'g, (Node, Weight)>)>,
+    },
+}
+
+impl<'g> WeightsMut<'g> {
+    /// Pesos de uma matriz de adjacência `node_count × node_count` guardada por linhas, em que
+    /// `present` indica quais posições têm aresta.
+    pub fn matrix(
+        node_count: usize,
+        links: &'g mut [Weight],
+        present: &'g bitvec::slice::BitSlice,
+    ) -> Self {
+        Self(WeightsMutKind::Matrix {
+            node_count,
+            links: links.iter_mut().enumerate(),
+            present,
+        })
+    }
+
+    /// Pesos de listas de adjacência de pares `(destino, peso)`, uma por nó.
+    pub fn lists(adjacency: &'g mut [Vec<(Node, Weight)>]) -> Self {
+        Self(WeightsMutKind::Lists {
+            rows: adjacency.iter_mut().enumerate(),
+            current: None,
+        })
+    }
+}
+
+impl<'g> Iterator for WeightsMut<'g> {
+    type Item = (Node, Node, &'g mut Weight);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            WeightsMutKind::Matrix {
+                node_count,
+                links,
+                present,
+            } => links
+                .find(|(i, _)| present[*i])
+                // A linha é a origem e a coluna o destino.
+                .map(|(i, w)| ((i / *node_count) as Node, (i % *node_count) as Node, w)),
+            WeightsMutKind::Lists { rows, current } => loop {
+                if let Some((a, list)) = current {
+                    if let Some((b, w)) = list.next() {
+                        return Some((*a, *b, w));
+                    }
+                }
+                let (a, list) = rows.next()?;
+                *current = Some((a as Node, list.iter_mut()));
+            },
+        }
+    }
+}
+
 impl std::ops::Index<(Node, Node)> for dyn Graph + '_ {
     type Output = Weight;
     fn index(&self, (a, b): (Node, Node)) -> &Self::Output {
@@ -80,26 +364,67 @@ impl std::ops::Index<(Node, Node)> for dyn Graph + '_ {
     }
 }
 
-impl std::ops::IndexMut<(Node, Node)> for dyn Graph + '_ {
+impl std::ops::Index<(Node, Node)> for dyn GraphMut + '_ {
+    type Output = Weight;
+    fn index(&self, (a, b): (Node, Node)) -> &Self::Output {
+        self.get_edge_weight_ref(a, b).unwrap()
+    }
+}
+
+impl std::ops::IndexMut<(Node, Node)> for dyn GraphMut + '_ {
     fn index_mut(&mut self, (a, b): (Node, Node)) -> &mut Self::Output {
         self.get_edge_weight_mut(a, b).unwrap()
     }
 }
 
 /// Struct que representa um grafo implementado por matriz de adjacência.
+///
+/// A existência de cada aresta é registrada separadamente do seu peso, então arestas de peso
+/// zero ou negativo são válidas.
 #[derive(Default, Debug, Clone)]
 pub struct GraphMat {
     node_count: usize,
     links: Vec<Weight>,
+    /// `present[i]` indica se existe a aresta cujo peso está em `links[i]`.
+    present: BitVec,
+    directed: bool,
 }
 
-impl Graph for GraphMat {
+impl GraphMat {
+    /// Cria um grafo dirigido, em que `add_edge(a, b, w)` registra apenas o arco de `a` para `b`.
+    pub fn directed() -> Self {
+        Self {
+            directed: true,
+            ..Default::default()
+        }
+    }
+
+    fn index(&self, a: Node, b: Node) -> Option<usize> {
+        let (a, b) = (a as usize, b as usize);
+        (a < self.node_count && b < self.node_count).then_some(a * self.node_count + b)
+    }
+
+    /// Remove a aresta entre `a` e `b`, caso exista. Nós fora do grafo não têm arestas, então
+    /// nada é feito.
+    pub fn remove_edge(&mut self, a: Node, b: Node) {
+        let (Some(ab), Some(ba)) = (self.index(a, b), self.index(b, a)) else {
+            return;
+        };
+        self.present.set(ab, false);
+        if !self.directed {
+            self.present.set(ba, false);
+        }
+    }
+}
+
+impl GraphMut for GraphMat {
     fn add_node(&mut self) -> Node {
         let new_node = self.node_count as Node;
 
         let new_node_count = self.node_count + 1;
-        // Cria novo vetor cujo tamanho é `(node_count+1) ^ 2`
+        // Cria novos vetores cujo tamanho é `(node_count+1) ^ 2`
         let mut new_links = vec![0.into(); new_node_count.pow(2)];
+        let mut new_present = bitvec![0; new_node_count.pow(2)];
 
         // Caso haja nós no vetor, precisamos copiar as informações para o novo.
         if self.node_count > 0 {
@@ -114,76 +439,274 @@ impl Graph for GraphMat {
                     // Copia os pesos das arestas do antigo vetor para o novo.
                     .copy_from_slice(old_line);
             }
+            // Faz o mesmo com os bits de presença das arestas.
+            for (row, old_line) in self.present.chunks(self.node_count).enumerate() {
+                let start = row * new_node_count;
+                new_present[start..start + self.node_count].copy_from_bitslice(old_line);
+            }
         }
 
         self.links = new_links;
+        self.present = new_present;
         self.node_count += 1;
 
         new_node
     }
+    fn get_edge_weight_mut(&mut self, a: Node, b: Node) -> Option<&mut Weight> {
+        let idx = self.index(a, b)?;
+        self.present[idx].then(|| &mut self.links[idx])
+    }
+    fn add_edge(&mut self, a: Node, b: Node, weight: Weight) {
+        // Sem a verificação, um nó fora do grafo poderia cair em outra linha da matriz.
+        let (Some(ab), Some(ba)) = (self.index(a, b), self.index(b, a)) else {
+            panic!(
+                "aresta ({a}, {b}) fora de um grafo com {} nós",
+                self.node_count
+            );
+        };
+        // Registra a ligação para o nó `a`
+        self.links[ab] = weight;
+        self.present.set(ab, true);
+        // Registra a ligação para o nó `b`, caso as arestas não tenham orientação
+        if !self.directed {
+            self.links[ba] = weight;
+            self.present.set(ba, true);
+        }
+    }
+    fn weights_mut(&mut self) -> WeightsMut<'_> {
+        WeightsMut::matrix(self.node_count, &mut self.links, &self.present)
+    }
+}
+
+impl Graph for GraphMat {
     fn node_count(&self) -> usize {
         self.node_count
     }
     fn get_edge_weight_ref(&self, a: Node, b: Node) -> Option<&Weight> {
-        let idx = a as usize * self.node_count + b as usize;
-        let w = self.links.get(idx)?;
-        if *w == 0.0 {
-            None
-        } else {
-            Some(w)
+        let idx = self.index(a, b)?;
+        self.present[idx].then(|| &self.links[idx])
+    }
+    fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    fn incident_edges(&self, a: Node) -> IncidentEdges<'_> {
+        let row = (a as usize * self.node_count)..((a as usize + 1) * self.node_count);
+        IncidentEdges::row(a, &self.present[row.clone()], &self.links[row])
+    }
+}
+
+impl std::ops::Index<(Node, Node)> for GraphMat {
+    type Output = Weight;
+    fn index(&self, (a, b): (Node, Node)) -> &Self::Output {
+        self.get_edge_weight_ref(a, b).unwrap()
+    }
+}
+
+impl std::ops::IndexMut<(Node, Node)> for GraphMat {
+    fn index_mut(&mut self, (a, b): (Node, Node)) -> &mut Self::Output {
+        self.get_edge_weight_mut(a, b).unwrap()
+    }
+}
+
+/// Struct que representa um grafo implementado por lista de adjacência.
+///
+/// Indicado para grafos esparsos: ocupa O(n + m) de memória, e `add_node`, `add_edge` e
+/// `get_edge_weight` têm custo O(1) amortizado.
+#[derive(Default, Debug, Clone)]
+pub struct GraphList {
+    adjacency: Vec<Vec<(Node, Weight)>>,
+    /// Posição de cada ligação `(a, b)` na lista de `a`, para não percorrer a lista ao buscar
+    /// ou substituir uma aresta.
+    positions: HashMap<(Node, Node), usize>,
+    directed: bool,
+}
+
+impl GraphList {
+    /// Cria um grafo dirigido, em que cada lista contém apenas os arcos de saída do nó.
+    pub fn directed() -> Self {
+        Self {
+            directed: true,
+            ..Default::default()
         }
     }
 
-    fn get_edge_weight_mut(&mut self, a: Node, b: Node) -> Option<&mut Weight> {
-        let idx = a as usize * self.node_count + b as usize;
-        let w = self.links.get_mut(idx)?;
-        if *w == 0.0 {
-            None
-        } else {
-            Some(w)
+    /// Cria um grafo vazio, dirigido ou não, com espaço reservado para `node_count` nós.
+    pub fn with_capacity(node_count: usize, directed: bool) -> Self {
+        Self {
+            adjacency: Vec::with_capacity(node_count),
+            directed,
+            ..Default::default()
         }
     }
-    fn edges(&self) -> Set<Edge> {
-        self.links
-            .iter()
-            // Iteramos sobre cópias em vez de referências
-            .copied()
-            // Adicionamos um contador à cada elemento
-            .enumerate()
-            // Filtra links cujo peso é 0
-            .filter(|(_, weight)| *weight > 0.0.into())
-            // Transforma uma tupla de posição e peso em `Edge`.
-            .map(|(i, weight)| {
-                let y = i / self.node_count;
-                let x = i % self.node_count;
-                Edge(x as Node, y as Node, weight)
-            })
-            .collect()
+
+    /// Cria um grafo não dirigido a partir de linhas no formato aceito por [`fill_graph`].
+    pub fn from_input(input_data: &[Vec<u32>]) -> Self {
+        let node_count = input_data.first().and_then(|head| head.first());
+        let mut graph = Self::with_capacity(node_count.copied().unwrap_or(0) as usize, false);
+        fill_graph(input_data, &mut graph);
+        graph
+    }
+
+    fn position(&self, a: Node, b: Node) -> Option<usize> {
+        self.positions.get(&(a, b)).copied()
+    }
+
+    // Registra a ligação de `a` para `b`, substituindo o peso caso ela já exista.
+    fn set_link(&mut self, a: Node, b: Node, weight: Weight) {
+        let list = &mut self.adjacency[a as usize];
+        match self.positions.entry((a, b)) {
+            Entry::Occupied(entry) => list[*entry.get()].1 = weight,
+            Entry::Vacant(entry) => {
+                entry.insert(list.len());
+                list.push((b, weight));
+            }
+        }
+    }
+}
+
+impl GraphMut for GraphList {
+    fn add_node(&mut self) -> Node {
+        self.adjacency.push(Vec::new());
+        (self.adjacency.len() - 1) as Node
     }
     fn add_edge(&mut self, a: Node, b: Node, weight: Weight) {
-        // Converte nós em `usizes` para simplificar a indexação.
-        let a = a as usize;
-        let b = b as usize;
-        // Registra a ligação para o nó `a`
-        self.links[a * self.node_count + b] = weight;
-        // Registra a ligação para o nó `b`
-        self.links[b * self.node_count + a] = weight;
+        self.set_link(a, b, weight);
+        if a != b && !self.directed {
+            self.set_link(b, a, weight);
+        }
+    }
+    fn weights_mut(&mut self) -> WeightsMut<'_> {
+        WeightsMut::lists(&mut self.adjacency)
+    }
+    fn get_edge_weight_mut(&mut self, a: Node, b: Node) -> Option<&mut Weight> {
+        let i = self.position(a, b)?;
+        Some(&mut self.adjacency[a as usize][i].1)
     }
+}
 
-    fn get_node_edges(&self, a: Node) -> Set<Edge> {
-        let a = a as usize;
-        self.links[(a * self.node_count)..((a + 1) * self.node_count)]
-            .iter()
-            .enumerate()
-            .filter(|(_, w)| **w != 0.0)
-            .map(|(b, w)| Edge(a as Node, b as Node, *w))
-            .collect()
+impl Graph for GraphList {
+    fn is_directed(&self) -> bool {
+        self.directed
+    }
+    fn node_count(&self) -> usize {
+        self.adjacency.len()
+    }
+    fn incident_edges(&self, a: Node) -> IncidentEdges<'_> {
+        IncidentEdges::list(a, &self.adjacency[a as usize])
+    }
+    fn get_edge_weight_ref(&self, a: Node, b: Node) -> Option<&Weight> {
+        let i = self.position(a, b)?;
+        Some(&self.adjacency[a as usize][i].1)
+    }
+}
+
+impl std::ops::Index<(Node, Node)> for GraphList {
+    type Output = Weight;
+    fn index(&self, (a, b): (Node, Node)) -> &Self::Output {
+        self.get_edge_weight_ref(a, b).unwrap()
+    }
+}
+
+impl std::ops::IndexMut<(Node, Node)> for GraphList {
+    fn index_mut(&mut self, (a, b): (Node, Node)) -> &mut Self::Output {
+        self.get_edge_weight_mut(a, b).unwrap()
+    }
+}
+
+type WeightCache = Mutex<Box<[(Node, Node, Weight)]>>;
+
+/// Grafo completo implícito: armazena apenas as coordenadas das cidades e calcula o peso de
+/// cada aresta sob demanda, ocupando O(n) de memória.
+///
+/// Como os pesos não são armazenados, `get_edge_weight_ref` sempre retorna `None`, e o grafo não
+/// implementa [`GraphMut`]: as cidades são adicionadas com [`CoordGraph::add_city`].
+#[derive(Debug, Default)]
+pub struct CoordGraph {
+    coords: Vec<[Weight; 2]>,
+    metric: utils::Metric,
+    // Cache de mapeamento direto indexada por um hash do par de nós. Protegida por um `Mutex`
+    // para que o grafo possa ser compartilhado entre threads.
+    cache: Option<WeightCache>,
+}
+
+impl CoordGraph {
+    pub fn new(metric: utils::Metric) -> Self {
+        Self {
+            metric,
+            ..Default::default()
+        }
+    }
+
+    /// Lê as cidades de um arquivo TSPLIB.
+    pub fn read(file: &mut dyn BufRead) -> io::Result<Self> {
+        let (metric, coords) = utils::read_tsp_coords(file)?;
+        Ok(Self {
+            coords,
+            ..Self::new(metric)
+        })
+    }
+
+    /// Habilita uma cache de `slots` pesos. Vale a pena apenas para métricas caras, como
+    /// [`utils::Metric::Geo`].
+    pub fn with_cache(mut self, slots: usize) -> Self {
+        let empty = (Node::MAX, Node::MAX, Weight::from(0.0));
+        self.cache = Some(Mutex::new(vec![empty; slots.max(1)].into()));
+        self
+    }
+
+    pub fn add_city(&mut self, coord: [Weight; 2]) -> Node {
+        self.coords.push(coord);
+        (self.coords.len() - 1) as Node
+    }
+
+    pub fn coords(&self) -> &[[Weight; 2]] {
+        &self.coords
+    }
+
+    pub fn metric(&self) -> utils::Metric {
+        self.metric
+    }
+
+    fn distance(&self, a: Node, b: Node) -> Weight {
+        self.metric
+            .distance(self.coords[a as usize], self.coords[b as usize])
+    }
+}
+
+impl Graph for CoordGraph {
+    fn node_count(&self) -> usize {
+        self.coords.len()
+    }
+    fn incident_edges(&self, a: Node) -> IncidentEdges<'_> {
+        IncidentEdges::computed(a, self)
+    }
+    fn get_edge_weight_ref(&self, _a: Node, _b: Node) -> Option<&Weight> {
+        None
+    }
+    fn get_edge_weight(&self, a: Node, b: Node) -> Option<Weight> {
+        if a as usize >= self.coords.len() || b as usize >= self.coords.len() {
+            return None;
+        }
+        // Se outra thread estiver usando a cache, é mais barato calcular de novo do que esperar.
+        let Some(mut cache) = self.cache.as_ref().and_then(|cache| cache.try_lock().ok()) else {
+            return Some(self.distance(a, b));
+        };
+        // As métricas são simétricas, então (a, b) e (b, a) ocupam a mesma posição.
+        let key = (a.min(b), a.max(b));
+        let hash = ((key.0 as u64) << 32 | key.1 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let index = (hash >> 32) as usize % cache.len();
+        let slot = &mut cache[index];
+        if (slot.0, slot.1) != key {
+            *slot = (key.0, key.1, self.distance(a, b));
+        }
+        Some(slot.2)
     }
 }
 
 /// Dado um vetor de linhas no formato "a b w", onde a e b são vértices e w é o peso da aresta
 /// entre eles, preenche o grafo `graph`.
-pub fn fill_graph(input_data: &[Vec<u32>], graph: &mut dyn Graph) {
+pub fn fill_graph(input_data: &[Vec<u32>], graph: &mut dyn GraphMut) {
     // Separa o vetor entre o primeiro elemento e o resto.
     let (head, tail) = input_data.split_first().expect("Vetor veio vazio");
     // Tenta desestruturar o vetor `head` em dois valores, executando o `else`
@@ -237,6 +760,51 @@ pub fn open_file() -> BufReader<File> {
     BufReader::new(file)
 }
 
+/// Lê as flags `--flag`, `--flag valor` ou `--flag=valor` de `args`. Um argumento que parece
+/// uma flag nunca é tomado como valor da anterior: em `--tenure --diversify`, `--tenure` fica
+/// sem valor.
+fn parse_flags(
+    args: impl IntoIterator<Item = OsString>,
+) -> Result<HashMap<String, Option<String>>, lexopt::Error> {
+    use lexopt::prelude::*;
+
+    let mut flags = HashMap::new();
+    let mut parser = lexopt::Parser::from_args(args);
+    while let Some(arg) = parser.next()? {
+        match arg {
+            Long(flag) => {
+                let flag = format!("--{flag}");
+                let value = match parser.values() {
+                    Ok(mut values) => values.next().map(|v| v.string()).transpose()?,
+                    Err(_) => None,
+                };
+                flags.insert(flag, value);
+            }
+            arg => return Err(arg.unexpected()),
+        }
+    }
+    Ok(flags)
+}
+
+/// Flags passadas na linha de comando depois do arquivo de entrada, lidas uma única vez.
+fn flags() -> &'static HashMap<String, Option<String>> {
+    static FLAGS: OnceLock<HashMap<String, Option<String>>> = OnceLock::new();
+    FLAGS.get_or_init(|| {
+        parse_flags(std::env::args_os().skip(2))
+            .unwrap_or_else(|e| panic!("Argumentos inválidos: {e}"))
+    })
+}
+
+/// Indica se `flag` foi passada na linha de comando, depois do arquivo de entrada.
+pub fn has_flag(flag: &str) -> bool {
+    flags().contains_key(flag)
+}
+
+/// Valor passado logo após `flag` na linha de comando, como em `--flag valor`.
+pub fn flag_value(flag: &str) -> Option<String> {
+    flags().get(flag)?.clone()
+}
+
 use std::fmt::Debug;
 use std::io::Write;
 pub fn debug_to_kw(val: &dyn Debug) -> String {
@@ -250,3 +818,43 @@ pub fn debug_to_kw(val: &dyn Debug) -> String {
         .trim()
         .replace(",", "")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_flags() {
+        let args = ["--tenure", "--diversify", "--alpha", "0.5", "--problem=3"];
+        let flags = parse_flags(args.map(OsString::from)).unwrap();
+        assert_eq!(flags["--tenure"], None);
+        assert_eq!(flags["--diversify"], None);
+        assert_eq!(flags["--alpha"].as_deref(), Some("0.5"));
+        assert_eq!(flags["--problem"].as_deref(), Some("3"));
+        // argumentos soltos não pertencem a nenhuma flag
+        assert!(parse_flags(["--alpha", "1", "2"].map(OsString::from)).is_err());
+    }
+
+    #[test]
+    fn graph_list_replaces_edges() {
+        let mut g = GraphList::with_capacity(3, false);
+        g.add_nodes(3);
+        g.add_edge(0, 1, 1.0.into());
+        g.add_edge(1, 2, 2.0.into());
+        g.add_edge(1, 0, 3.0.into());
+        assert_eq!(g.get_edge_weight(0, 1), Some(3.0.into()));
+        assert_eq!(g.incident_edges(1).count(), 2);
+        assert_eq!(g.get_edge_weight(0, 2), None);
+        *g.get_edge_weight_mut(2, 1).unwrap() = 5.0.into();
+        assert_eq!(g.get_edge_weight(2, 1), Some(5.0.into()));
+    }
+
+    #[test]
+    fn graph_list_with_capacity_keeps_direction() {
+        let mut g = GraphList::with_capacity(2, true);
+        g.add_nodes(2);
+        g.add_edge(0, 1, 1.0.into());
+        assert!(g.is_directed());
+        assert_eq!(g.get_edge_weight(1, 0), None);
+    }
+}
@@ -0,0 +1,192 @@
+//! Conjunto de soluções de elite e religamento de caminhos entre elas.
+use rand::{seq::SliceRandom, Rng};
+
+use crate::knapsack::KnapsackSolution;
+
+/// Soluções entre as quais se pode traçar um caminho de movimentos.
+pub trait Relink: Clone {
+    /// Indica se a solução é estritamente melhor que `other`.
+    fn is_better(&self, other: &Self) -> bool;
+    /// Quantidade de movimentos necessários para transformar uma solução na outra.
+    fn distance(&self, other: &Self) -> usize;
+    /// Aplica, entre os movimentos que aproximam a solução de `guide`, o que leva à melhor
+    /// avaliação. Devolve `false` se as soluções já são iguais.
+    fn step_towards(&mut self, guide: &Self) -> bool;
+}
+
+/// Permutações: a distância é a de trocas, e cada passo troca um nó fora do lugar para a
+/// posição que ocupa em `guide`.
+impl Relink for crate::tsp::Solution<'_> {
+    fn is_better(&self, other: &Self) -> bool {
+        self < other
+    }
+    fn distance(&self, other: &Self) -> usize {
+        // n menos a quantidade de ciclos da permutação que leva uma rota à outra
+        let n = self.nodes.len();
+        let mut position = vec![0; n];
+        for (i, &node) in other.nodes.iter().enumerate() {
+            position[node as usize] = i;
+        }
+        let mut visited = vec![false; n];
+        let mut cycles = 0;
+        for start in 0..n {
+            if visited[start] {
+                continue;
+            }
+            cycles += 1;
+            let mut i = start;
+            while !visited[i] {
+                visited[i] = true;
+                i = position[self.nodes[i] as usize];
+            }
+        }
+        n - cycles
+    }
+    fn step_towards(&mut self, guide: &Self) -> bool {
+        let n = self.nodes.len();
+        let mut position = vec![0; n];
+        for (i, &node) in self.nodes.iter().enumerate() {
+            position[node as usize] = i;
+        }
+        let best = (0..n)
+            .filter(|i| self.nodes[*i] != guide.nodes[*i])
+            .map(|i| (i, position[guide.nodes[i] as usize]))
+            .min_by_key(|(i, j)| self.swap_delta(*i, *j));
+        match best {
+            Some((i, j)) => {
+                *self = self.swap(i, j);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Vetores binários: a distância é a de Hamming, e cada passo inverte um ítem em que as
+/// soluções diferem.
+impl<S: KnapsackSolution> Relink for S {
+    fn is_better(&self, other: &Self) -> bool {
+        self > other
+    }
+    fn distance(&self, other: &Self) -> usize {
+        (self.items().clone() ^ other.items()).count_ones()
+    }
+    fn step_towards(&mut self, guide: &Self) -> bool {
+        let differ = self.items().clone() ^ guide.items();
+        match differ.iter_ones().max_by_key(|i| self.flip_delta(*i)) {
+            Some(i) => {
+                self.flip_in_place(i);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Sentido do religamento.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Da nova solução em direção à de elite.
+    Forward,
+    /// Da solução de elite em direção à nova.
+    Backward,
+    /// Das duas pontas ao mesmo tempo, alternando os passos, até que se encontrem.
+    Mixed,
+}
+
+impl std::str::FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "forward" => Ok(Self::Forward),
+            "backward" => Ok(Self::Backward),
+            "mixed" => Ok(Self::Mixed),
+            _ => Err(format!("Sentido de religamento desconhecido: {s}")),
+        }
+    }
+}
+
+/// Percorre o caminho entre `s` e `elite` e devolve a melhor solução intermediária, sem contar
+/// as pontas. Devolve `None` se as soluções estiverem a menos de dois passos.
+pub fn path_relinking<S: Relink>(s: &S, elite: &S, direction: Direction) -> Option<S> {
+    let (mut from, mut to) = match direction {
+        Direction::Forward | Direction::Mixed => (s.clone(), elite.clone()),
+        Direction::Backward => (elite.clone(), s.clone()),
+    };
+    let mut best: Option<S> = None;
+    loop {
+        if !from.step_towards(&to) || from.distance(&to) == 0 {
+            break best;
+        }
+        if best.as_ref().is_none_or(|best| from.is_better(best)) {
+            best = Some(from.clone());
+        }
+        if direction == Direction::Mixed {
+            std::mem::swap(&mut from, &mut to);
+        }
+    }
+}
+
+/// Conjunto limitado das melhores soluções encontradas, mantido diverso: uma nova solução só
+/// entra se estiver a pelo menos `min_distance` de todas as outras, a não ser que seja melhor que
+/// todas.
+#[derive(Debug, Clone)]
+pub struct ElitePool<S> {
+    members: Vec<S>,
+    pub capacity: usize,
+    pub min_distance: usize,
+}
+
+impl<S: Relink> ElitePool<S> {
+    pub fn new(capacity: usize, min_distance: usize) -> Self {
+        Self {
+            members: Vec::with_capacity(capacity),
+            capacity,
+            min_distance,
+        }
+    }
+
+    pub fn members(&self) -> &[S] {
+        &self.members
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn choose(&self, rand: &mut impl Rng) -> Option<&S> {
+        self.members.choose(rand)
+    }
+
+    /// Tenta adicionar a solução ao conjunto. Quando está cheio, ela substitui, entre os
+    /// membros piores que ela, o mais parecido. Devolve se foi adicionada.
+    pub fn insert(&mut self, s: &S) -> bool {
+        let best_overall = self.members.iter().all(|m| s.is_better(m));
+        let distances = self
+            .members
+            .iter()
+            .map(|m| s.distance(m))
+            .collect::<Vec<_>>();
+        if distances.contains(&0) {
+            return false;
+        }
+        if !best_overall && distances.iter().any(|d| *d < self.min_distance) {
+            return false;
+        }
+        if self.members.len() < self.capacity {
+            self.members.push(s.clone());
+            return true;
+        }
+        let replaced = (0..self.members.len())
+            .filter(|i| s.is_better(&self.members[*i]))
+            .min_by_key(|i| distances[*i]);
+        match replaced {
+            Some(i) => {
+                self.members[i] = s.clone();
+                true
+            }
+            None => false,
+        }
+    }
+}
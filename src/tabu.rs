@@ -0,0 +1,229 @@
+//! Memória da busca tabu, compartilhada entre os problemas. Os movimentos são descritos por
+//! atributos, índices de `0` a `attributes - 1` (um ítem da mochila, um par de posições do
+//! caixeiro), e a lista tabu guarda para cada atributo a iteração até a qual ele está banido, de
+//! modo que consultar e banir custam O(1) e nada precisa ser decrementado a cada iteração.
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use rand::Rng;
+
+/// Como a duração do banimento é escolhida.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tenure {
+    /// Sempre `tabu_memory`.
+    Fixed,
+    /// Busca tabu robusta de Taillard: sorteada em `tenure_min..=tenure_max` a cada movimento.
+    Robust,
+    /// Busca tabu reativa de Battiti e Tecchiolli: começa em `tabu_memory`, cresce quando uma
+    /// solução é revisitada e diminui após um período sem repetições, limitada a
+    /// `tenure_min..=tenure_max`.
+    Reactive,
+}
+
+impl std::str::FromStr for Tenure {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fixed" => Ok(Self::Fixed),
+            "robust" => Ok(Self::Robust),
+            "reactive" => Ok(Self::Reactive),
+            _ => Err(format!("Duração tabu desconhecida: {s}")),
+        }
+    }
+}
+
+/// Quando um movimento banido pode ser feito mesmo assim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aspiration {
+    /// Nunca.
+    None,
+    /// Quando leva a uma solução melhor que a melhor encontrada.
+    Best,
+    /// Quando leva a uma solução melhor que a corrente.
+    Improving,
+}
+
+impl std::str::FromStr for Aspiration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "best" => Ok(Self::Best),
+            "improving" => Ok(Self::Improving),
+            _ => Err(format!("Critério de aspiração desconhecido: {s}")),
+        }
+    }
+}
+
+impl Aspiration {
+    /// Se um movimento banido que leva ao valor `candidate` é permitido. Os valores são
+    /// minimizados: problemas de max devem trocar o sinal.
+    pub fn allows(self, candidate: f64, current: f64, best: f64) -> bool {
+        match self {
+            Aspiration::None => false,
+            Aspiration::Best => candidate < best,
+            Aspiration::Improving => candidate < current,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TabuParams {
+    pub tenure: Tenure,
+    pub tabu_memory: usize,
+    pub tenure_min: usize,
+    pub tenure_max: usize,
+    /// Peso da penalidade por frequência, em unidades da função objetivo: um atributo movido em
+    /// todas as iterações é penalizado em exatamente esse valor. 0 desliga a diversificação.
+    pub diversification: f64,
+    pub aspiration: Aspiration,
+}
+
+/// Hash de uma solução, para detectar revisitas na busca reativa.
+pub fn hash(s: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    s.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[derive(Debug, Clone)]
+pub struct TabuMemory {
+    params: TabuParams,
+    iteration: usize,
+    /// Iteração até a qual cada atributo está banido (exclusive).
+    until: Vec<usize>,
+    /// Quantas vezes cada atributo foi movido.
+    frequency: Vec<usize>,
+    /// Duração atual na busca reativa.
+    tenure: f64,
+    /// Última iteração em que cada solução foi visitada, esquecida após [`TabuMemory::window`]
+    /// iterações.
+    visits: HashMap<u64, usize>,
+    /// Média móvel do intervalo entre revisitas e a iteração da última mudança de duração.
+    cycle: f64,
+    last_change: usize,
+}
+
+/// Quantas vezes a duração máxima uma visita é lembrada na busca reativa. Revisitas mais
+/// espaçadas que isso não indicam um ciclo que a duração possa quebrar.
+const VISITS_WINDOW: usize = 100;
+
+impl TabuMemory {
+    /// Falha se as durações robusta ou reativa tiverem `tenure_min > tenure_max`. Na busca
+    /// reativa, a duração inicial é `tabu_memory` limitada a `tenure_min..=tenure_max`.
+    pub fn new(attributes: usize, params: TabuParams) -> Result<Self, String> {
+        if params.tenure != Tenure::Fixed && params.tenure_min > params.tenure_max {
+            return Err(format!(
+                "Duração tabu mínima ({}) maior que a máxima ({})",
+                params.tenure_min, params.tenure_max
+            ));
+        }
+        let tenure = match params.tenure {
+            Tenure::Reactive => params
+                .tabu_memory
+                .clamp(params.tenure_min, params.tenure_max),
+            _ => params.tabu_memory,
+        };
+        Ok(Self {
+            params,
+            iteration: 0,
+            until: vec![0; attributes],
+            frequency: vec![0; attributes],
+            tenure: tenure as f64,
+            visits: HashMap::new(),
+            cycle: params.tabu_memory as f64,
+            last_change: 0,
+        })
+    }
+
+    /// Por quantas iterações uma visita é lembrada na busca reativa.
+    fn window(&self) -> usize {
+        VISITS_WINDOW * self.params.tenure_max.max(1)
+    }
+
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// Duração do banimento atual, na busca reativa.
+    pub fn tenure(&self) -> usize {
+        self.tenure.round() as usize
+    }
+
+    pub fn is_tabu(&self, attribute: usize) -> bool {
+        self.until[attribute] > self.iteration
+    }
+
+    /// Iteração em que o banimento do atributo termina.
+    pub fn expires(&self, attribute: usize) -> usize {
+        self.until[attribute]
+    }
+
+    /// Se um movimento banido que leva ao valor `candidate` é permitido pelo critério de
+    /// aspiração, com a convenção de [`Aspiration::allows`].
+    pub fn aspires(&self, candidate: f64, current: f64, best: f64) -> bool {
+        self.params.aspiration.allows(candidate, current, best)
+    }
+
+    /// Penalidade por frequência de um atributo, a ser somada à piora de movimentos que não
+    /// melhoram a solução corrente.
+    pub fn penalty(&self, attribute: usize) -> f64 {
+        if self.iteration == 0 {
+            return 0.0;
+        }
+        self.params.diversification * self.frequency[attribute] as f64 / self.iteration as f64
+    }
+
+    /// Bane os atributos do movimento feito nesta iteração e conta sua frequência.
+    pub fn forbid(&mut self, attributes: impl IntoIterator<Item = usize>, rand: &mut impl Rng) {
+        let TabuParams {
+            tenure,
+            tabu_memory,
+            tenure_min,
+            tenure_max,
+            ..
+        } = self.params;
+        let tenure = match tenure {
+            Tenure::Fixed => tabu_memory,
+            Tenure::Robust => rand.gen_range(tenure_min..=tenure_max),
+            Tenure::Reactive => self.tenure(),
+        };
+        for attribute in attributes {
+            // `+ 1` para que o atributo fique banido por `tenure` iterações a partir da próxima
+            self.until[attribute] = self.iteration + tenure + 1;
+            self.frequency[attribute] += 1;
+        }
+    }
+
+    /// Encerra a iteração, registrando a solução alcançada. Na busca reativa, aumenta a duração
+    /// se a solução já foi visitada, e a diminui se não há repetições há mais que o intervalo
+    /// médio entre elas.
+    pub fn next_iteration(&mut self, solution_hash: u64) {
+        if self.params.tenure == Tenure::Reactive {
+            let (min, max) = (self.params.tenure_min as f64, self.params.tenure_max as f64);
+            match self.visits.insert(solution_hash, self.iteration) {
+                Some(last) => {
+                    let length = (self.iteration - last) as f64;
+                    self.cycle = 0.1 * length + 0.9 * self.cycle;
+                    self.tenure = (self.tenure * 1.1 + 1.0).min(max);
+                    self.last_change = self.iteration;
+                }
+                None if (self.iteration - self.last_change) as f64 > self.cycle => {
+                    self.tenure = (self.tenure * 0.9 - 1.0).max(min);
+                    self.last_change = self.iteration;
+                }
+                None => (),
+            }
+            // esquece as visitas antigas de tempos em tempos, para que a tabela não cresça
+            // indefinidamente: ela guarda no máximo as visitas de duas janelas
+            let window = self.window();
+            if self.iteration % window == 0 {
+                let iteration = self.iteration;
+                self.visits.retain(|_, last| iteration - *last <= window);
+            }
+        }
+        self.iteration += 1;
+    }
+}
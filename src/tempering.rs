@@ -0,0 +1,193 @@
+//! Têmpera paralela (troca de réplicas): várias cópias da solução são recozidas ao mesmo tempo,
+//! cada uma em uma temperatura fixa e em sua própria thread. Entre as rodadas, réplicas de
+//! temperaturas vizinhas trocam de lugar pelo critério de Metropolis, de modo que boas soluções
+//! descem para as temperaturas baixas e as ruins sobem para se diversificar.
+use rand::Rng;
+
+use crate::knapsack::{KnapsackSolution, Neighbourhood};
+use crate::tsp;
+
+/// Soluções que podem ser recozidas em uma thread própria.
+pub trait Replica: Clone + Send {
+    type Neighbourhood: Copy + Send;
+
+    /// Energia da solução, a ser minimizada.
+    fn energy(&self) -> f64;
+    /// Se a solução pode ser devolvida como resultado.
+    fn is_feasible(&self) -> bool;
+    /// Sorteia um vizinho e o aceita com a probabilidade do SA na temperatura `temp`.
+    fn metropolis(&mut self, neighbourhood: Self::Neighbourhood, temp: f64, rand: &mut impl Rng);
+}
+
+impl Replica for tsp::Solution<'_> {
+    type Neighbourhood = tsp::Neighbourhood;
+
+    fn energy(&self) -> f64 {
+        self.value.0
+    }
+    fn is_feasible(&self) -> bool {
+        true
+    }
+    fn metropolis(&mut self, neighbourhood: tsp::Neighbourhood, temp: f64, rand: &mut impl Rng) {
+        let s_prime = self.random_move(neighbourhood, rand);
+        let worsening = (s_prime.value - self.value).0;
+        if worsening <= 0.0 || rand.gen::<f64>() < (-worsening / temp).exp() {
+            *self = s_prime;
+        }
+    }
+}
+
+/// Como a mochila é um problema de max, a energia é o valor com o sinal trocado.
+impl<S: KnapsackSolution + Send> Replica for S {
+    type Neighbourhood = Neighbourhood;
+
+    fn energy(&self) -> f64 {
+        -self.value().0
+    }
+    fn is_feasible(&self) -> bool {
+        KnapsackSolution::is_feasible(self)
+    }
+    fn metropolis(&mut self, neighbourhood: Neighbourhood, temp: f64, rand: &mut impl Rng) {
+        let mv = self.random_move(neighbourhood, rand);
+        let delta = self.move_delta(&mv).0;
+        if delta >= 0.0 || rand.gen::<f64>() < (delta / temp).exp() {
+            self.apply(&mv);
+        }
+        // cada réplica ajusta sua própria penalidade, se ela for adaptativa
+        self.adapt_penalty();
+    }
+}
+
+/// Trocas tentadas e aceitas entre um par de temperaturas vizinhas.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SwapStats {
+    pub attempts: usize,
+    pub accepted: usize,
+}
+
+impl SwapStats {
+    pub fn rate(&self) -> f64 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.accepted as f64 / self.attempts as f64
+        }
+    }
+}
+
+/// `n` temperaturas em progressão geométrica de `temp_min` a `temp_max`, em ordem crescente.
+/// Vazia se `n` for 0.
+pub fn geometric_ladder(temp_min: f64, temp_max: f64, n: usize) -> Vec<f64> {
+    match n {
+        0 => return vec![],
+        1 => return vec![temp_min],
+        _ => (),
+    }
+    let ratio = (temp_max / temp_min).powf(1.0 / (n - 1) as f64);
+    (0..n).map(|i| temp_min * ratio.powi(i as i32)).collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct Tempering {
+    /// Temperatura de cada réplica, em ordem crescente.
+    temps: Vec<f64>,
+    pub rounds: usize,
+    /// Passos de Metropolis de cada réplica por rodada.
+    pub sweep: usize,
+    stats: Vec<SwapStats>,
+}
+
+impl Tempering {
+    pub fn new(temps: Vec<f64>, rounds: usize, sweep: usize) -> Self {
+        let pairs = temps.len().saturating_sub(1);
+        Self {
+            temps,
+            rounds,
+            sweep,
+            stats: vec![SwapStats::default(); pairs],
+        }
+    }
+
+    pub fn temps(&self) -> &[f64] {
+        &self.temps
+    }
+
+    /// Estatísticas de troca de cada par `(temps[i], temps[i + 1])`, acumuladas entre as
+    /// execuções.
+    pub fn stats(&self) -> &[SwapStats] {
+        &self.stats
+    }
+
+    /// Imprime uma linha por par de temperaturas no formato `t_low;t_high;attempts;accepted;rate`.
+    pub fn print_stats(&self) {
+        println!("t_low;t_high;attempts;accepted;rate");
+        for (pair, stats) in self.temps.windows(2).zip(&self.stats) {
+            println!(
+                "{};{};{};{};{}",
+                pair[0],
+                pair[1],
+                stats.attempts,
+                stats.accepted,
+                stats.rate()
+            );
+        }
+    }
+
+    /// Recoze uma cópia de `s0` em cada temperatura e devolve a melhor solução viável encontrada
+    /// por qualquer réplica, se houver.
+    pub fn run<S: Replica>(&mut self, s0: &S, neighbourhood: S::Neighbourhood) -> Option<S> {
+        let mut replicas = vec![s0.clone(); self.temps.len()];
+        let mut best = s0.is_feasible().then(|| s0.clone());
+
+        let mut rand = rand::thread_rng();
+        for round in 0..self.rounds {
+            let sweep = self.sweep;
+            // cada réplica recoze em sua thread e devolve a melhor solução viável que visitou
+            let found = std::thread::scope(|scope| {
+                let handles = replicas
+                    .iter_mut()
+                    .zip(&self.temps)
+                    .map(|(replica, &temp)| {
+                        scope.spawn(move || {
+                            let mut rand = rand::thread_rng();
+                            let mut best: Option<S> = None;
+                            for _ in 0..sweep {
+                                replica.metropolis(neighbourhood, temp, &mut rand);
+                                if replica.is_feasible()
+                                    && best.as_ref().is_none_or(|b| replica.energy() < b.energy())
+                                {
+                                    best = Some(replica.clone());
+                                }
+                            }
+                            best
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .filter_map(|handle| handle.join().unwrap())
+                    .collect::<Vec<_>>()
+            });
+            for s in found {
+                if best.as_ref().is_none_or(|b| s.energy() < b.energy()) {
+                    best = Some(s);
+                }
+            }
+
+            // alterna entre os pares pares e ímpares, para que cada réplica participe de no
+            // máximo uma troca por rodada
+            for i in (round % 2..self.temps.len().saturating_sub(1)).step_by(2) {
+                let stats = &mut self.stats[i];
+                stats.attempts += 1;
+                // aceita com probabilidade exp((1/T_i - 1/T_{i+1}) (E_i - E_{i+1}))
+                let exponent = (1.0 / self.temps[i] - 1.0 / self.temps[i + 1])
+                    * (replicas[i].energy() - replicas[i + 1].energy());
+                if exponent >= 0.0 || rand.gen::<f64>() < exponent.exp() {
+                    replicas.swap(i, i + 1);
+                    stats.accepted += 1;
+                }
+            }
+        }
+        best
+    }
+}
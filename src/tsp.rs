@@ -1,8 +1,237 @@
+use std::collections::BinaryHeap;
+
+use bitvec::bitvec;
 use rand::{seq::SliceRandom, Rng};
 
-use crate::{Graph, Node, Weight};
+use crate::{utils::Metric, Graph, Node, Weight, IMPROVEMENT};
+
+pub mod christofides;
+pub mod lns;
 
 pub type NodeList = Box<[Node]>;
+
+/// Lista de candidatos: para cada nó, seus `k` vizinhos mais próximos em ordem crescente de
+/// distância.
+///
+/// Restringir os movimentos aos candidatos evita varrer todos os nós em instâncias grandes.
+#[derive(Debug, Clone)]
+pub struct CandidateList {
+    k: usize,
+    /// Vizinhos de cada nó; os de `a` ficam em `neighbours[starts[a]..starts[a + 1]]`.
+    neighbours: Vec<Node>,
+    starts: Vec<usize>,
+}
+
+impl CandidateList {
+    /// Calcula a lista consultando todos os pesos do grafo, em O(n² log k). Em grafos
+    /// esparsos, só as arestas existentes são consideradas, e um nó pode ter menos de `k`
+    /// candidatos.
+    pub fn new(g: &dyn Graph, k: usize) -> Self {
+        let k = k.min(g.node_count().saturating_sub(1));
+        let mut this = Self::with_capacity(g.node_count(), k);
+        for a in g.nodes() {
+            let mut nearest = BinaryHeap::with_capacity(k + 1);
+            for b in g.nodes().filter(|b| *b != a) {
+                let Some(w) = g.get_edge_weight(a, b) else {
+                    continue;
+                };
+                nearest.push((w, b));
+                if nearest.len() > k {
+                    nearest.pop();
+                }
+            }
+            this.push(nearest);
+        }
+        this
+    }
+
+    fn with_capacity(n: usize, k: usize) -> Self {
+        let mut starts = Vec::with_capacity(n + 1);
+        starts.push(0);
+        Self {
+            k,
+            neighbours: Vec::with_capacity(n * k),
+            starts,
+        }
+    }
+
+    /// Adiciona os candidatos do próximo nó.
+    fn push(&mut self, nearest: BinaryHeap<(Weight, Node)>) {
+        self.neighbours
+            .extend(nearest.into_sorted_vec().into_iter().map(|(_, b)| b));
+        self.starts.push(self.neighbours.len());
+    }
+
+    /// Calcula a lista a partir das coordenadas das cidades.
+    ///
+    /// Para métricas planares, distribui as cidades numa grade uniforme e visita as células em
+    /// anéis crescentes ao redor de cada cidade, parando assim que o anel seguinte está mais
+    /// longe que o k-ésimo vizinho encontrado. Em instâncias bem distribuídas isso custa
+    /// O(n k log k) em vez de O(n²).
+    pub fn from_coords(coords: &[[Weight; 2]], metric: Metric, k: usize) -> Self {
+        let n = coords.len();
+        let k = k.min(n.saturating_sub(1));
+        if !metric.is_planar() {
+            // Sem poda possível, compara todos os pares.
+            let mut this = Self::with_capacity(n, k);
+            for a in 0..n {
+                let mut nearest = BinaryHeap::with_capacity(k + 1);
+                for b in (0..n).filter(|b| *b != a) {
+                    nearest.push((metric.distance(coords[a], coords[b]), b as Node));
+                    if nearest.len() > k {
+                        nearest.pop();
+                    }
+                }
+                this.push(nearest);
+            }
+            return this;
+        }
+
+        let grid = Grid::new(coords);
+        let mut this = Self::with_capacity(n, k);
+        for a in 0..n {
+            let (cx, cy) = grid.cell_of(coords[a]);
+            let mut nearest = BinaryHeap::<(Weight, Node)>::with_capacity(k + 1);
+            for ring in 0..=grid.side {
+                for b in grid.ring(cx, cy, ring).filter(|b| *b != a) {
+                    nearest.push((metric.distance(coords[a], coords[b]), b as Node));
+                    if nearest.len() > k {
+                        nearest.pop();
+                    }
+                }
+                // Cidades fora dos anéis já visitados estão a pelo menos `ring` células de
+                // distância em algum dos eixos.
+                let bound = metric.distance(
+                    [0.0.into(); 2],
+                    [(grid.cell_size * ring as f64).into(), 0.0.into()],
+                );
+                if nearest.len() == k && bound > nearest.peek().unwrap().0 {
+                    break;
+                }
+            }
+            this.push(nearest);
+        }
+        this
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Vizinhos mais próximos de `a`, do mais próximo ao mais distante.
+    pub fn get(&self, a: Node) -> &[Node] {
+        let a = a as usize;
+        &self.neighbours[self.starts[a]..self.starts[a + 1]]
+    }
+}
+/// Grade uniforme sobre as coordenadas das cidades, com cerca de duas cidades por célula.
+struct Grid {
+    min: [f64; 2],
+    side: usize,
+    cell_size: f64,
+    /// Cidades ordenadas por célula; as cidades da célula `c` ficam em
+    /// `cities[starts[c]..starts[c + 1]]`.
+    cities: Vec<usize>,
+    starts: Vec<usize>,
+}
+
+impl Grid {
+    fn new(coords: &[[Weight; 2]]) -> Self {
+        let axis = |i: usize| {
+            let values = coords.iter().map(|c| *c[i]);
+            let min = values.clone().fold(f64::INFINITY, f64::min);
+            let max = values.fold(f64::NEG_INFINITY, f64::max);
+            (min, max - min)
+        };
+        let ((min_x, width), (min_y, height)) = (axis(0), axis(1));
+        let side = ((coords.len() as f64 / 2.0).sqrt().ceil() as usize).max(1);
+        // evita células de tamanho 0 quando todas as cidades coincidem em um eixo
+        let cell_size = (width.max(height) / side as f64).max(f64::MIN_POSITIVE);
+
+        let mut this = Self {
+            min: [min_x, min_y],
+            side,
+            cell_size,
+            cities: Vec::new(),
+            starts: vec![0; side * side + 1],
+        };
+        // ordenação por contagem das cidades de acordo com sua célula
+        let cells = coords
+            .iter()
+            .map(|c| {
+                let (x, y) = this.cell_of(*c);
+                y * side + x
+            })
+            .collect::<Vec<_>>();
+        for &cell in &cells {
+            this.starts[cell + 1] += 1;
+        }
+        for i in 0..side * side {
+            this.starts[i + 1] += this.starts[i];
+        }
+        let mut next = this.starts.clone();
+        this.cities = vec![0; coords.len()];
+        for (city, &cell) in cells.iter().enumerate() {
+            this.cities[next[cell]] = city;
+            next[cell] += 1;
+        }
+        this
+    }
+
+    fn cell_of(&self, coord: [Weight; 2]) -> (usize, usize) {
+        let cell = |i: usize| {
+            let c = ((*coord[i] - self.min[i]) / self.cell_size) as usize;
+            c.min(self.side - 1)
+        };
+        (cell(0), cell(1))
+    }
+
+    /// Cidades nas células a exatamente `ring` células de distância de `(cx, cy)`.
+    fn ring(&self, cx: usize, cy: usize, ring: usize) -> impl Iterator<Item = usize> + '_ {
+        let (cx, cy, ring, side) = (cx as isize, cy as isize, ring as isize, self.side as isize);
+        (cy - ring..=cy + ring)
+            .flat_map(move |y| (cx - ring..=cx + ring).map(move |x| (x, y)))
+            .filter(move |(x, y)| (x - cx).abs().max((y - cy).abs()) == ring)
+            .filter(move |(x, y)| (0..side).contains(x) && (0..side).contains(y))
+            .flat_map(move |(x, y)| {
+                let cell = (y * side + x) as usize;
+                &self.cities[self.starts[cell]..self.starts[cell + 1]]
+            })
+            .copied()
+    }
+}
+
+/// Vizinhanças da rota, da menor para a maior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// Troca dois nós de posição.
+    Swap,
+    TwoOpt,
+    /// Move um segmento de até 3 nós.
+    OrOpt,
+    /// Troca de lugar dois segmentos consecutivos.
+    ThreeOpt,
+}
+
+impl std::str::FromStr for Neighbourhood {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "swap" => Ok(Self::Swap),
+            "2opt" => Ok(Self::TwoOpt),
+            "oropt" => Ok(Self::OrOpt),
+            "3opt" => Ok(Self::ThreeOpt),
+            _ => Err(format!("Vizinhança desconhecida: {s}")),
+        }
+    }
+}
+
+impl Neighbourhood {
+    /// Todas as vizinhanças, na ordem usual de uma descida em vizinhança variável.
+    pub const ALL: [Neighbourhood; 4] = [Self::Swap, Self::TwoOpt, Self::OrOpt, Self::ThreeOpt];
+}
+
 #[derive(Clone)]
 pub struct Solution<'g> {
     pub nodes: NodeList,
@@ -20,7 +249,7 @@ impl std::cmp::PartialEq for Solution<'_> {
 
 impl std::cmp::PartialOrd for Solution<'_> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.value.partial_cmp(&other.value)
+        Some(self.cmp(other))
     }
 }
 impl std::cmp::Eq for Solution<'_> {}
@@ -46,8 +275,8 @@ impl<'g> Solution<'g> {
     pub fn new(nodes: impl Into<Box<[Node]>>, graph: &'g dyn Graph) -> Self {
         let nodes = nodes.into();
         Self {
-            value: solution_value(&*nodes, graph),
-            nodes: nodes.into(),
+            value: solution_value(&nodes, graph),
+            nodes,
             graph,
         }
     }
@@ -63,12 +292,350 @@ impl<'g> Solution<'g> {
         Self::new(nodes, graph)
     }
 
+    /// Heurística do vizinho mais próximo: a partir do nó 0, visita o candidato mais próximo
+    /// ainda não visitado, recorrendo a uma busca em todos os nós apenas quando todos os
+    /// candidatos já foram visitados.
+    pub fn nearest_neighbour(g: &'g dyn Graph, candidates: &CandidateList) -> Self {
+        let n = g.node_count();
+        let mut visited = bitvec![0; n];
+        let mut nodes = Vec::with_capacity(n);
+        let mut current = 0;
+        for _ in 0..n {
+            visited.set(current as usize, true);
+            nodes.push(current);
+            let candidate = candidates
+                .get(current)
+                .iter()
+                .copied()
+                .find(|b| !visited[*b as usize]);
+            let Some(next) = candidate.or_else(|| {
+                visited
+                    .iter_zeros()
+                    .map(|b| b as Node)
+                    .min_by_key(|b| g.get_edge_weight(current, *b).unwrap())
+            }) else {
+                break;
+            };
+            current = next;
+        }
+        Self::new(nodes, g)
+    }
+
+    pub fn graph(&self) -> &'g dyn Graph {
+        self.graph
+    }
+
+    fn weight(&self, a: Node, b: Node) -> Weight {
+        self.graph.get_edge_weight(a, b).unwrap()
+    }
+
+    /// Nó na posição `i` da rota, tratando as posições de forma circular.
+    fn at(&self, i: usize) -> Node {
+        self.nodes[i % self.nodes.len()]
+    }
+
     pub fn swap(&self, a: usize, b: usize) -> Self {
         let mut nodes = self.nodes.clone();
         nodes.swap(a, b);
         Self::new(nodes, self.graph)
     }
 
+    /// Variação do custo causada por `swap(a, b)`, calculada em O(1).
+    pub fn swap_delta(&self, a: usize, b: usize) -> Weight {
+        let n = self.nodes.len();
+        if a == b {
+            return 0.0.into();
+        }
+        // Arestas afetadas, identificadas pela posição de sua origem. Quando `a` e `b` são
+        // vizinhos, algumas se repetem.
+        let mut affected = [(a + n - 1) % n, a, (b + n - 1) % n, b];
+        affected.sort_unstable();
+        let swapped = |i: usize| match i % n {
+            i if i == a => self.nodes[b],
+            i if i == b => self.nodes[a],
+            i => self.nodes[i],
+        };
+        affected
+            .iter()
+            .enumerate()
+            .filter(|(k, i)| *k == 0 || affected[k - 1] != **i)
+            .map(|(_, &i)| {
+                self.weight(swapped(i), swapped(i + 1)) - self.weight(self.at(i), self.at(i + 1))
+            })
+            .sum()
+    }
+
+    /// Movimento 2-opt: remove as arestas que entram e saem do segmento `nodes[i..=j]` e
+    /// reconecta a rota invertendo o segmento.
+    pub fn two_opt(&self, i: usize, j: usize) -> Self {
+        let mut nodes = self.nodes.clone();
+        nodes[i..=j].reverse();
+        Self::new(nodes, self.graph)
+    }
+
+    /// Variação do custo causada por `two_opt(i, j)`.
+    ///
+    /// Em grafos não dirigidos custa O(1). Em grafos dirigidos o segmento invertido passa a ser
+    /// percorrido no sentido oposto, então o custo de suas arestas também muda e o cálculo
+    /// custa O(j - i).
+    pub fn two_opt_delta(&self, i: usize, j: usize) -> Weight {
+        let n = self.nodes.len();
+        if j - i + 1 >= n {
+            // Inverter a rota inteira não tem arestas de entrada e saída.
+            return self.two_opt(i, j).value - self.value;
+        }
+        let (prev, first) = (self.at(i + n - 1), self.nodes[i]);
+        let (last, next) = (self.nodes[j], self.at(j + 1));
+        let mut delta = self.weight(prev, last) + self.weight(first, next)
+            - self.weight(prev, first)
+            - self.weight(last, next);
+        if self.graph.is_directed() {
+            for k in i..j {
+                let (a, b) = (self.nodes[k], self.nodes[k + 1]);
+                delta += self.weight(b, a) - self.weight(a, b);
+            }
+        }
+        delta
+    }
+
+    /// Movimento Or-opt: move o segmento `nodes[i..i + len]` para logo após o nó na posição `j`,
+    /// sem inverter o segmento. Portanto é exato também em instâncias assimétricas.
+    ///
+    /// `j` não pode estar dentro do segmento nem imediatamente antes dele.
+    pub fn or_opt(&self, i: usize, len: usize, j: usize) -> Self {
+        debug_assert!(i + len <= self.nodes.len());
+        let anchor = self.nodes[j];
+        let segment = &self.nodes[i..i + len];
+        let mut nodes = Vec::with_capacity(self.nodes.len());
+        for (k, &node) in self.nodes.iter().enumerate() {
+            if (i..i + len).contains(&k) {
+                continue;
+            }
+            nodes.push(node);
+            if node == anchor {
+                nodes.extend_from_slice(segment);
+            }
+        }
+        Self::new(nodes, self.graph)
+    }
+
+    /// Variação do custo causada por `or_opt(i, len, j)`, calculada em O(1).
+    pub fn or_opt_delta(&self, i: usize, len: usize, j: usize) -> Weight {
+        let n = self.nodes.len();
+        let (prev, first) = (self.at(i + n - 1), self.nodes[i]);
+        let (last, next) = (self.nodes[i + len - 1], self.at(i + len));
+        let (a, b) = (self.nodes[j], self.at(j + 1));
+        self.weight(prev, next) + self.weight(a, first) + self.weight(last, b)
+            - self.weight(prev, first)
+            - self.weight(last, next)
+            - self.weight(a, b)
+    }
+
+    /// Movimento 3-opt sem inversões: troca de lugar os segmentos consecutivos `nodes[i..j]` e
+    /// `nodes[j..k]`, com `0 < i < j < k <= n`. Como nenhum segmento é invertido, vale também
+    /// para instâncias assimétricas.
+    pub fn three_opt(&self, i: usize, j: usize, k: usize) -> Self {
+        debug_assert!(0 < i && i < j && j < k && k <= self.nodes.len());
+        let nodes = self.nodes[..i]
+            .iter()
+            .chain(&self.nodes[j..k])
+            .chain(&self.nodes[i..j])
+            .chain(&self.nodes[k..])
+            .copied()
+            .collect::<Box<[_]>>();
+        Self::new(nodes, self.graph)
+    }
+
+    /// Variação do custo causada por `three_opt(i, j, k)`, calculada em O(1).
+    pub fn three_opt_delta(&self, i: usize, j: usize, k: usize) -> Weight {
+        let (a, b) = (self.nodes[i - 1], self.nodes[i]);
+        let (c, d) = (self.nodes[j - 1], self.nodes[j]);
+        let (e, f) = (self.nodes[k - 1], self.at(k));
+        self.weight(a, d) + self.weight(e, b) + self.weight(c, f)
+            - self.weight(a, b)
+            - self.weight(c, d)
+            - self.weight(e, f)
+    }
+
+    /// Aplica o primeiro movimento da vizinhança que melhora a rota. Devolve se encontrou algum.
+    pub fn improve(&mut self, neighbourhood: Neighbourhood) -> bool {
+        let n = self.nodes.len();
+        let improving = |delta: Weight| delta.0 < -IMPROVEMENT;
+        match neighbourhood {
+            Neighbourhood::Swap => {
+                for a in 0..n {
+                    for b in a + 1..n {
+                        if improving(self.swap_delta(a, b)) {
+                            *self = self.swap(a, b);
+                            return true;
+                        }
+                    }
+                }
+            }
+            Neighbourhood::TwoOpt => {
+                for i in 0..n.saturating_sub(1) {
+                    for j in i + 1..n {
+                        if improving(self.two_opt_delta(i, j)) {
+                            *self = self.two_opt(i, j);
+                            return true;
+                        }
+                    }
+                }
+            }
+            Neighbourhood::OrOpt => {
+                for len in 1..=3.min(n.saturating_sub(2)) {
+                    for i in 0..=n - len {
+                        for j in 0..n {
+                            // `j` não pode estar no segmento nem imediatamente antes dele
+                            if (i..i + len).contains(&j) || j == (i + n - 1) % n {
+                                continue;
+                            }
+                            if improving(self.or_opt_delta(i, len, j)) {
+                                *self = self.or_opt(i, len, j);
+                                return true;
+                            }
+                        }
+                    }
+                }
+            }
+            Neighbourhood::ThreeOpt => {
+                for i in 1..n {
+                    for j in i + 1..n {
+                        for k in j + 1..=n {
+                            if improving(self.three_opt_delta(i, j, k)) {
+                                *self = self.three_opt(i, j, k);
+                                return true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Solução vizinha aleatória na vizinhança dada.
+    pub fn random_move(&self, neighbourhood: Neighbourhood, rand: &mut impl Rng) -> Self {
+        let n = self.nodes.len();
+        if n < 4 {
+            return self.random_neighbour(rand);
+        }
+        match neighbourhood {
+            Neighbourhood::Swap => self.random_neighbour(rand),
+            Neighbourhood::TwoOpt => {
+                let mut ends = rand::seq::index::sample(rand, n, 2).into_vec();
+                ends.sort_unstable();
+                self.two_opt(ends[0], ends[1])
+            }
+            Neighbourhood::OrOpt => {
+                let len = rand.gen_range(1..=3);
+                let i = rand.gen_range(0..=n - len);
+                // posições fora do segmento e diferentes da imediatamente anterior
+                let j = loop {
+                    let j = rand.gen_range(0..n);
+                    if !(i..i + len).contains(&j) && j != (i + n - 1) % n {
+                        break j;
+                    }
+                };
+                self.or_opt(i, len, j)
+            }
+            Neighbourhood::ThreeOpt => {
+                let mut cuts = rand::seq::index::sample(rand, n, 3).into_vec();
+                cuts.sort_unstable();
+                self.three_opt(cuts[0] + 1, cuts[1] + 1, cuts[2] + 1)
+            }
+        }
+    }
+
+    /// Varre todos os movimentos 2-opt aplicando os que melhoram a rota, até que uma passada
+    /// inteira não encontre melhora. Devolve se a rota mudou.
+    pub fn two_opt_search(&mut self) -> bool {
+        let n = self.nodes.len();
+        let mut changed = false;
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 0..n.saturating_sub(1) {
+                for j in i + 1..n {
+                    if self.two_opt_delta(i, j).0 < -IMPROVEMENT {
+                        *self = self.two_opt(i, j);
+                        improved = true;
+                    }
+                }
+            }
+            changed |= improved;
+        }
+        changed
+    }
+
+    /// Varre todos os movimentos Or-opt com segmentos de até 3 nós aplicando os que melhoram a
+    /// rota, até que uma passada inteira não encontre melhora. Devolve se a rota mudou.
+    pub fn or_opt_search(&mut self) -> bool {
+        let n = self.nodes.len();
+        let mut changed = false;
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for len in 1..=3.min(n.saturating_sub(2)) {
+                for i in 0..=n - len {
+                    for j in 0..n {
+                        // `j` não pode estar no segmento nem imediatamente antes dele
+                        if (i..i + len).contains(&j) || j == (i + n - 1) % n {
+                            continue;
+                        }
+                        if self.or_opt_delta(i, len, j).0 < -IMPROVEMENT {
+                            *self = self.or_opt(i, len, j);
+                            improved = true;
+                        }
+                    }
+                }
+            }
+            changed |= improved;
+        }
+        changed
+    }
+
+    /// Busca local com 2-opt e Or-opt, alternadas até que nenhuma das duas melhore a rota.
+    pub fn local_search(&mut self) {
+        self.two_opt_search();
+        while self.or_opt_search() && self.two_opt_search() {}
+    }
+
+    /// Perturbação double-bridge: corta a rota em quatro segmentos `A B C D` e os reconecta como
+    /// `A C B D`. Não inverte segmentos, então vale também para instâncias assimétricas, e não
+    /// é desfeita facilmente por 2-opt ou Or-opt.
+    pub fn double_bridge(&self, rand: &mut impl Rng) -> Self {
+        let n = self.nodes.len();
+        if n < 8 {
+            return self.random_neighbour(rand);
+        }
+        let mut cuts = rand::seq::index::sample(rand, n - 1, 3).into_vec();
+        cuts.sort_unstable();
+        let [p1, p2, p3] = [cuts[0] + 1, cuts[1] + 1, cuts[2] + 1];
+        let nodes = self.nodes[..p1]
+            .iter()
+            .chain(&self.nodes[p2..p3])
+            .chain(&self.nodes[p1..p2])
+            .chain(&self.nodes[p3..])
+            .copied()
+            .collect::<Box<[_]>>();
+        Self::new(nodes, self.graph)
+    }
+
+    /// Perturbação que embaralha um segmento aleatório de até `max_len` nós. Rotas com menos de
+    /// dois nós não têm o que embaralhar e são devolvidas sem mudanças.
+    pub fn shuffle_segment(&self, max_len: usize, rand: &mut impl Rng) -> Self {
+        let n = self.nodes.len();
+        if n < 2 {
+            return self.clone();
+        }
+        let len = rand.gen_range(2..=max_len.clamp(2, n));
+        let start = rand.gen_range(0..=n - len);
+        let mut nodes = self.nodes.clone();
+        nodes[start..start + len].shuffle(rand);
+        Self::new(nodes, self.graph)
+    }
+
     pub fn random_neighbour(&self, rand: &mut impl Rng) -> Self {
         let span = 0..self.nodes.len();
         let a = rand.gen_range(span.clone());
@@ -82,3 +649,140 @@ impl<'g> Solution<'g> {
         self.swap(a, b)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{GraphMat, GraphMut};
+
+    /// Grafo completo com pesos inteiros aleatórios, diferentes em cada sentido se for dirigido.
+    fn complete_graph(n: usize, directed: bool, rand: &mut StdRng) -> GraphMat {
+        let mut g = if directed {
+            GraphMat::directed()
+        } else {
+            GraphMat::default()
+        };
+        g.add_nodes(n);
+        for a in 0..n as Node {
+            for b in 0..n as Node {
+                if a != b && (directed || a < b) {
+                    g.add_edge(a, b, (rand.gen_range(1..100) as f64).into());
+                }
+            }
+        }
+        g
+    }
+
+    /// Confere a variação calculada com a diferença entre as avaliações completas.
+    fn assert_delta(s: &Solution, delta: Weight, s_prime: &Solution, mv: &str) {
+        let expected = s_prime.value - s.value;
+        assert!(
+            (delta - expected).0.abs() < 1e-9,
+            "{mv}: variação {delta}, esperada {expected} em {:?}",
+            s.nodes
+        );
+    }
+
+    fn for_each_tour(mut check: impl FnMut(&Solution)) {
+        let mut rand = StdRng::seed_from_u64(42);
+        for directed in [false, true] {
+            for n in 3..=8 {
+                let g = complete_graph(n, directed, &mut rand);
+                for _ in 0..5 {
+                    let mut nodes = (0..n as Node).collect::<Vec<_>>();
+                    nodes.shuffle(&mut rand);
+                    check(&Solution::new(nodes, &g));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn swap_delta_matches_evaluation() {
+        for_each_tour(|s| {
+            let n = s.nodes.len();
+            for a in 0..n {
+                for b in 0..n {
+                    assert_delta(s, s.swap_delta(a, b), &s.swap(a, b), "swap");
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn two_opt_delta_matches_evaluation() {
+        for_each_tour(|s| {
+            let n = s.nodes.len();
+            for i in 0..n {
+                for j in i + 1..n {
+                    assert_delta(s, s.two_opt_delta(i, j), &s.two_opt(i, j), "2-opt");
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn or_opt_delta_matches_evaluation() {
+        for_each_tour(|s| {
+            let n = s.nodes.len();
+            for len in 1..n - 1 {
+                for i in 0..=n - len {
+                    for j in 0..n {
+                        // `j` fora do segmento e não imediatamente antes dele
+                        if (i..i + len).contains(&j) || (j + 1) % n == i {
+                            continue;
+                        }
+                        let delta = s.or_opt_delta(i, len, j);
+                        assert_delta(s, delta, &s.or_opt(i, len, j), "or-opt");
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn three_opt_delta_matches_evaluation() {
+        for_each_tour(|s| {
+            let n = s.nodes.len();
+            for i in 1..n {
+                for j in i + 1..n {
+                    for k in j + 1..=n {
+                        let delta = s.three_opt_delta(i, j, k);
+                        assert_delta(s, delta, &s.three_opt(i, j, k), "3-opt");
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn candidate_list_skips_missing_edges() {
+        let mut g = GraphMat::default();
+        g.add_nodes(4);
+        g.add_edge(0, 1, 3.0.into());
+        g.add_edge(0, 2, 1.0.into());
+        g.add_edge(2, 3, 2.0.into());
+        let candidates = CandidateList::new(&g, 2);
+        assert_eq!(candidates.get(0), [2, 1]);
+        assert_eq!(candidates.get(1), [0]);
+        assert_eq!(candidates.get(2), [0, 3]);
+        assert_eq!(candidates.get(3), [2]);
+    }
+
+    #[test]
+    fn shuffle_segment_on_tiny_tours() {
+        let mut rand = StdRng::seed_from_u64(59);
+        // uma rota de um nó só pode ser avaliada com um laço
+        let mut g = GraphMat::default();
+        g.add_node();
+        g.add_edge(0, 0, 0.0.into());
+        let s = Solution::sequential(&g);
+        assert_eq!(s.shuffle_segment(4, &mut rand).nodes, s.nodes);
+
+        let g = complete_graph(2, false, &mut rand);
+        let s = Solution::sequential(&g);
+        assert_eq!(s.shuffle_segment(4, &mut rand).value, s.value);
+    }
+}
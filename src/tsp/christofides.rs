@@ -0,0 +1,201 @@
+//! Construção de rotas pelo algoritmo de Christofides.
+//!
+//! Em instâncias métricas (que respeitam a desigualdade triangular) o emparelhamento exato
+//! garante uma rota no máximo 1,5 vezes o custo ótimo.
+use bitvec::bitvec;
+use itertools::Itertools;
+
+use crate::{algorithms, Edge, Graph, Node, Weight};
+
+use super::Solution;
+
+mod blossom;
+
+/// Método usado para emparelhar os vértices de grau ímpar da árvore geradora mínima.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Matching {
+    /// Emparelhamento perfeito de peso mínimo pelo algoritmo de blossom, em O(k³) para `k`
+    /// vértices ímpares. Mantém a garantia de 1,5, mas precisa de O(k²) memória.
+    Exact,
+    /// Emparelha gulosamente os pares mais próximos primeiro. Não tem garantia de qualidade
+    /// (o emparelhamento guloso pode custar Θ(k^log₂1,5) ≈ k^0,585 vezes o ótimo, segundo
+    /// Reingold e Tarjan), mas é bem mais rápido.
+    Greedy,
+}
+
+/// Fator de escala aplicado aos pesos antes do emparelhamento exato, que trabalha com inteiros.
+const MATCHING_SCALE: f64 = 1000.0;
+
+/// Peso da aresta entre `a` e `b`, que precisa existir.
+fn weight(g: &dyn Graph, a: Node, b: Node) -> Result<Weight, String> {
+    g.get_edge_weight(a, b)
+        .ok_or_else(|| format!("Christofides pressupõe um grafo completo, mas falta ({a}, {b})"))
+}
+
+fn greedy_matching(g: &dyn Graph, odd: &[Node]) -> Result<Vec<(Node, Node)>, String> {
+    let mut pairs = odd
+        .iter()
+        .copied()
+        .tuple_combinations()
+        .map(|(a, b)| Ok((weight(g, a, b)?, a, b)))
+        .collect::<Result<Vec<_>, String>>()?;
+    pairs.sort_unstable();
+
+    let mut matched = bitvec![0; g.node_count()];
+    let mut matching = Vec::with_capacity(odd.len() / 2);
+    for (_, a, b) in pairs {
+        if matched[a as usize] || matched[b as usize] {
+            continue;
+        }
+        matched.set(a as usize, true);
+        matched.set(b as usize, true);
+        matching.push((a, b));
+    }
+    Ok(matching)
+}
+
+fn exact_matching(g: &dyn Graph, odd: &[Node]) -> Result<Vec<(Node, Node)>, String> {
+    let pairs = (0..odd.len())
+        .tuple_combinations()
+        .map(|(i, j)| Ok((i, j, weight(g, odd[i], odd[j])?)))
+        .collect::<Result<Vec<_>, String>>()?;
+    let Some(max) = pairs.iter().map(|(_, _, w)| *w).max() else {
+        return Ok(vec![]);
+    };
+    // Um emparelhamento perfeito de peso mínimo é um emparelhamento de cardinalidade máxima
+    // com peso máximo sobre os pesos `max - w`.
+    let edges = pairs
+        .into_iter()
+        .map(|(i, j, w)| (i, j, ((max - w).0 * MATCHING_SCALE).round() as i64))
+        .collect_vec();
+    Ok(blossom::max_weight_matching(odd.len(), &edges, true)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, mate)| mate.filter(|j| i < *j).map(|j| (odd[i], odd[j])))
+        .collect())
+}
+
+/// Circuito euleriano pelo algoritmo de Hierholzer no multigrafo formado pelas arestas `edges`.
+fn eulerian_circuit(node_count: usize, edges: &[(Node, Node)]) -> Vec<Node> {
+    if node_count == 0 {
+        return vec![];
+    }
+    let mut adjacency = vec![Vec::new(); node_count];
+    for (id, &(a, b)) in edges.iter().enumerate() {
+        adjacency[a as usize].push((b, id));
+        adjacency[b as usize].push((a, id));
+    }
+    let mut used = bitvec![0; edges.len()];
+
+    let mut circuit = Vec::with_capacity(edges.len() + 1);
+    let mut stack = vec![0 as Node];
+    while let Some(&v) = stack.last() {
+        // descarta arestas já percorridas pelo outro lado
+        let neighbours = &mut adjacency[v as usize];
+        while neighbours.last().is_some_and(|(_, id)| used[*id]) {
+            neighbours.pop();
+        }
+        match neighbours.pop() {
+            Some((u, id)) => {
+                used.set(id, true);
+                stack.push(u);
+            }
+            None => circuit.push(stack.pop().unwrap()),
+        }
+    }
+    circuit
+}
+
+/// Constrói uma rota pelo algoritmo de Christofides: árvore geradora mínima, emparelhamento
+/// dos vértices de grau ímpar, circuito euleriano e atalhos sobre nós repetidos.
+///
+/// Falha em grafos dirigidos, aos quais o algoritmo não se aplica, em grafos vazios, que não têm
+/// rota, e em grafos incompletos em que falte alguma aresta usada pelo emparelhamento ou pelos
+/// atalhos.
+pub fn christofides<'g>(g: &'g dyn Graph, matching: Matching) -> Result<Solution<'g>, String> {
+    if g.is_directed() {
+        return Err("Christofides não se aplica a instâncias assimétricas".to_string());
+    }
+    let n = g.node_count();
+    if n == 0 {
+        return Err("Christofides precisa de ao menos um nó".to_string());
+    }
+    let mut edges = algorithms::prim(g)?
+        .into_iter()
+        .map(|Edge(a, b, _)| (a, b))
+        .collect_vec();
+
+    let mut degree = vec![0usize; n];
+    for &(a, b) in &edges {
+        degree[a as usize] += 1;
+        degree[b as usize] += 1;
+    }
+    let odd = g
+        .nodes()
+        .filter(|v| degree[*v as usize] % 2 == 1)
+        .collect_vec();
+
+    edges.extend(match matching {
+        Matching::Exact => exact_matching(g, &odd)?,
+        Matching::Greedy => greedy_matching(g, &odd)?,
+    });
+
+    // Atalhos: mantém apenas a primeira visita de cada nó.
+    let mut visited = bitvec![0; n];
+    let tour = eulerian_circuit(n, &edges)
+        .into_iter()
+        .filter(|v| {
+            let seen = visited[*v as usize];
+            visited.set(*v as usize, true);
+            !seen
+        })
+        .collect_vec();
+    // em um grafo desconexo o circuito não passa por todos os nós
+    if tour.len() < n {
+        return Err("Christofides pressupõe um grafo conexo".to_string());
+    }
+    for (a, b) in tour.iter().circular_tuple_windows() {
+        weight(g, *a, *b)?;
+    }
+    Ok(Solution::new(tour, g))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GraphMat, GraphMut};
+
+    fn square(complete: bool) -> GraphMat {
+        let mut g = GraphMat::default();
+        g.add_nodes(4);
+        for (a, b) in [(0, 1), (1, 2), (2, 3), (3, 0)] {
+            g.add_edge(a, b, 1.0.into());
+        }
+        if complete {
+            g.add_edge(0, 2, 2.0.into());
+            g.add_edge(1, 3, 2.0.into());
+        }
+        g
+    }
+
+    #[test]
+    fn builds_a_tour() {
+        let g = square(true);
+        for matching in [Matching::Exact, Matching::Greedy] {
+            let s = christofides(&g, matching).unwrap();
+            let mut nodes = s.nodes.to_vec();
+            nodes.sort();
+            assert_eq!(nodes, [0, 1, 2, 3]);
+            assert_eq!(s.value, 4.0);
+        }
+    }
+
+    #[test]
+    fn rejects_incomplete_graphs() {
+        let mut g = square(false);
+        g.remove_edge(3, 0);
+        for matching in [Matching::Exact, Matching::Greedy] {
+            assert!(christofides(&g, matching).is_err());
+        }
+    }
+}
@@ -0,0 +1,631 @@
+//! Emparelhamento de peso máximo em grafos gerais pelo algoritmo de blossom de Edmonds, em
+//! O(n³).
+//!
+//! Tradução direta da implementação de referência de Joris van Rantwijk (`mwmatching.py`),
+//! mantendo os mesmos nomes de variáveis para facilitar a comparação. Os pesos são inteiros para
+//! que as variáveis duais permaneçam exatas.
+
+const NONE: usize = usize::MAX;
+
+struct Matcher<'e> {
+    nvertex: usize,
+    edges: &'e [(usize, usize, i64)],
+    /// `endpoint[p]` é o vértice ao qual a extremidade `p` está ligada. A aresta `k` tem as
+    /// extremidades `2k` e `2k+1`.
+    endpoint: Vec<usize>,
+    /// Extremidades remotas das arestas incidentes a cada vértice.
+    neighbend: Vec<Vec<usize>>,
+    /// `mate[v]` é a extremidade remota da aresta emparelhada de `v`.
+    mate: Vec<usize>,
+    /// 0 = livre, 1 = S, 2 = T (5 marca temporariamente um S durante `scan_blossom`).
+    label: Vec<u8>,
+    labelend: Vec<usize>,
+    inblossom: Vec<usize>,
+    blossomparent: Vec<usize>,
+    blossomchilds: Vec<Vec<usize>>,
+    blossombase: Vec<usize>,
+    blossomendps: Vec<Vec<usize>>,
+    bestedge: Vec<usize>,
+    blossombestedges: Vec<Option<Vec<usize>>>,
+    unusedblossoms: Vec<usize>,
+    dualvar: Vec<i64>,
+    allowedge: Vec<bool>,
+    queue: Vec<usize>,
+}
+
+impl<'e> Matcher<'e> {
+    fn new(nvertex: usize, edges: &'e [(usize, usize, i64)]) -> Self {
+        let nedge = edges.len();
+        let maxweight = edges.iter().map(|e| e.2).max().unwrap_or(0).max(0);
+        let endpoint = (0..2 * nedge)
+            .map(|p| {
+                let (i, j, _) = edges[p / 2];
+                if p % 2 == 0 {
+                    i
+                } else {
+                    j
+                }
+            })
+            .collect();
+        let mut neighbend = vec![Vec::new(); nvertex];
+        for (k, &(i, j, _)) in edges.iter().enumerate() {
+            neighbend[i].push(2 * k + 1);
+            neighbend[j].push(2 * k);
+        }
+        let mut dualvar = vec![maxweight; nvertex];
+        dualvar.resize(2 * nvertex, 0);
+        let mut blossombase: Vec<usize> = (0..nvertex).collect();
+        blossombase.resize(2 * nvertex, NONE);
+        Self {
+            nvertex,
+            edges,
+            endpoint,
+            neighbend,
+            mate: vec![NONE; nvertex],
+            label: vec![0; 2 * nvertex],
+            labelend: vec![NONE; 2 * nvertex],
+            inblossom: (0..nvertex).collect(),
+            blossomparent: vec![NONE; 2 * nvertex],
+            blossomchilds: vec![Vec::new(); 2 * nvertex],
+            blossombase,
+            blossomendps: vec![Vec::new(); 2 * nvertex],
+            bestedge: vec![NONE; 2 * nvertex],
+            blossombestedges: vec![None; 2 * nvertex],
+            unusedblossoms: (nvertex..2 * nvertex).collect(),
+            dualvar,
+            allowedge: vec![false; nedge],
+            queue: Vec::new(),
+        }
+    }
+
+    fn slack(&self, k: usize) -> i64 {
+        let (i, j, wt) = self.edges[k];
+        self.dualvar[i] + self.dualvar[j] - 2 * wt
+    }
+
+    fn blossom_leaves(&self, b: usize) -> Vec<usize> {
+        let mut leaves = Vec::new();
+        let mut stack = vec![b];
+        while let Some(t) = stack.pop() {
+            if t < self.nvertex {
+                leaves.push(t);
+            } else {
+                // empilha em ordem reversa para manter a ordem original das folhas
+                stack.extend(self.blossomchilds[t].iter().rev());
+            }
+        }
+        leaves
+    }
+
+    fn assign_label(&mut self, w: usize, t: u8, p: usize) {
+        let b = self.inblossom[w];
+        self.label[w] = t;
+        self.label[b] = t;
+        self.labelend[w] = p;
+        self.labelend[b] = p;
+        self.bestedge[w] = NONE;
+        self.bestedge[b] = NONE;
+        if t == 1 {
+            // b se tornou um S-blossom, seus vértices entram na fila.
+            let leaves = self.blossom_leaves(b);
+            self.queue.extend(leaves);
+        } else if t == 2 {
+            // b se tornou um T-blossom, o vértice emparelhado com sua base vira S.
+            let base = self.blossombase[b];
+            let mate = self.mate[base];
+            self.assign_label(self.endpoint[mate], 1, mate ^ 1);
+        }
+    }
+
+    /// Procura um novo blossom ou caminho aumentante a partir de `v` e `w`. Retorna a base do
+    /// blossom ou `NONE` caso um caminho aumentante tenha sido encontrado.
+    fn scan_blossom(&mut self, mut v: usize, mut w: usize) -> usize {
+        let mut path = Vec::new();
+        let mut base = NONE;
+        while v != NONE || w != NONE {
+            let mut b = self.inblossom[v];
+            if self.label[b] & 4 != 0 {
+                base = self.blossombase[b];
+                break;
+            }
+            path.push(b);
+            self.label[b] = 5;
+            if self.labelend[b] == NONE {
+                // a raiz da árvore alternante foi alcançada
+                v = NONE;
+            } else {
+                v = self.endpoint[self.labelend[b]];
+                b = self.inblossom[v];
+                v = self.endpoint[self.labelend[b]];
+            }
+            if w != NONE {
+                std::mem::swap(&mut v, &mut w);
+            }
+        }
+        for b in path {
+            self.label[b] = 1;
+        }
+        base
+    }
+
+    fn add_blossom(&mut self, base: usize, k: usize) {
+        let (mut v, mut w, _) = self.edges[k];
+        let bb = self.inblossom[base];
+        let mut bv = self.inblossom[v];
+        let mut bw = self.inblossom[w];
+        let b = self.unusedblossoms.pop().unwrap();
+        self.blossombase[b] = base;
+        self.blossomparent[b] = NONE;
+        self.blossomparent[bb] = b;
+        let mut path = Vec::new();
+        let mut endps = Vec::new();
+        while bv != bb {
+            self.blossomparent[bv] = b;
+            path.push(bv);
+            endps.push(self.labelend[bv]);
+            v = self.endpoint[self.labelend[bv]];
+            bv = self.inblossom[v];
+        }
+        path.push(bb);
+        path.reverse();
+        endps.reverse();
+        endps.push(2 * k);
+        while bw != bb {
+            self.blossomparent[bw] = b;
+            path.push(bw);
+            endps.push(self.labelend[bw] ^ 1);
+            w = self.endpoint[self.labelend[bw]];
+            bw = self.inblossom[w];
+        }
+        self.blossomchilds[b] = path.clone();
+        self.blossomendps[b] = endps;
+        self.label[b] = 1;
+        self.labelend[b] = self.labelend[bb];
+        self.dualvar[b] = 0;
+        for v in self.blossom_leaves(b) {
+            if self.label[self.inblossom[v]] == 2 {
+                // T-vértices viram S-vértices e precisam ser explorados
+                self.queue.push(v);
+            }
+            self.inblossom[v] = b;
+        }
+        // Calcula as arestas de menor folga do novo blossom para cada S-blossom vizinho.
+        let mut bestedgeto = vec![NONE; 2 * self.nvertex];
+        for bv in path {
+            let nblists: Vec<Vec<usize>> = match self.blossombestedges[bv].take() {
+                Some(list) => vec![list],
+                None => self
+                    .blossom_leaves(bv)
+                    .into_iter()
+                    .map(|v| self.neighbend[v].iter().map(|p| p / 2).collect())
+                    .collect(),
+            };
+            for nblist in nblists {
+                for k in nblist {
+                    let (mut i, mut j, _) = self.edges[k];
+                    if self.inblossom[j] == b {
+                        std::mem::swap(&mut i, &mut j);
+                    }
+                    let bj = self.inblossom[j];
+                    if bj != b
+                        && self.label[bj] == 1
+                        && (bestedgeto[bj] == NONE || self.slack(k) < self.slack(bestedgeto[bj]))
+                    {
+                        bestedgeto[bj] = k;
+                    }
+                }
+            }
+            self.bestedge[bv] = NONE;
+        }
+        let list: Vec<usize> = bestedgeto.into_iter().filter(|&k| k != NONE).collect();
+        self.bestedge[b] = NONE;
+        for &k in &list {
+            if self.bestedge[b] == NONE || self.slack(k) < self.slack(self.bestedge[b]) {
+                self.bestedge[b] = k;
+            }
+        }
+        self.blossombestedges[b] = Some(list);
+    }
+
+    fn expand_blossom(&mut self, b: usize, endstage: bool) {
+        for s in self.blossomchilds[b].clone() {
+            self.blossomparent[s] = NONE;
+            if s < self.nvertex {
+                self.inblossom[s] = s;
+            } else if endstage && self.dualvar[s] == 0 {
+                self.expand_blossom(s, endstage);
+            } else {
+                for v in self.blossom_leaves(s) {
+                    self.inblossom[v] = s;
+                }
+            }
+        }
+        if !endstage && self.label[b] == 2 {
+            // O blossom era um T-blossom: reetiqueta seus sub-blossoms ao longo do caminho
+            // par da entrada até a base.
+            let childs = self.blossomchilds[b].clone();
+            let endps = self.blossomendps[b].clone();
+            let len = childs.len() as isize;
+            let at = |j: isize| ((j % len + len) % len) as usize;
+            let entrychild = self.inblossom[self.endpoint[self.labelend[b] ^ 1]];
+            let mut j = childs.iter().position(|&c| c == entrychild).unwrap() as isize;
+            let (jstep, endptrick) = if j & 1 == 1 {
+                j -= len;
+                (1, 0)
+            } else {
+                (-1, 1)
+            };
+            let mut p = self.labelend[b];
+            while j != 0 {
+                self.label[self.endpoint[p ^ 1]] = 0;
+                let q = endps[at(j - endptrick)];
+                self.label[self.endpoint[q ^ endptrick as usize ^ 1]] = 0;
+                self.assign_label(self.endpoint[p ^ 1], 2, p);
+                self.allowedge[q / 2] = true;
+                j += jstep;
+                p = endps[at(j - endptrick)] ^ endptrick as usize;
+                self.allowedge[p / 2] = true;
+                j += jstep;
+            }
+            let bv = childs[at(j)];
+            self.label[self.endpoint[p ^ 1]] = 2;
+            self.label[bv] = 2;
+            self.labelend[self.endpoint[p ^ 1]] = p;
+            self.labelend[bv] = p;
+            self.bestedge[bv] = NONE;
+            j += jstep;
+            while childs[at(j)] != entrychild {
+                let bv = childs[at(j)];
+                if self.label[bv] == 1 {
+                    j += jstep;
+                    continue;
+                }
+                let reached = self
+                    .blossom_leaves(bv)
+                    .into_iter()
+                    .find(|&v| self.label[v] != 0);
+                if let Some(v) = reached {
+                    self.label[v] = 0;
+                    self.label[self.endpoint[self.mate[self.blossombase[bv]]]] = 0;
+                    self.assign_label(v, 2, self.labelend[v]);
+                }
+                j += jstep;
+            }
+        }
+        self.label[b] = 0;
+        self.labelend[b] = NONE;
+        self.blossomchilds[b].clear();
+        self.blossomendps[b].clear();
+        self.blossombase[b] = NONE;
+        self.blossombestedges[b] = None;
+        self.bestedge[b] = NONE;
+        self.unusedblossoms.push(b);
+    }
+
+    fn augment_blossom(&mut self, b: usize, v: usize) {
+        let mut t = v;
+        while self.blossomparent[t] != b {
+            t = self.blossomparent[t];
+        }
+        if t >= self.nvertex {
+            self.augment_blossom(t, v);
+        }
+        let childs = self.blossomchilds[b].clone();
+        let endps = self.blossomendps[b].clone();
+        let len = childs.len() as isize;
+        let at = |j: isize| ((j % len + len) % len) as usize;
+        let i = childs.iter().position(|&c| c == t).unwrap();
+        let mut j = i as isize;
+        let (jstep, endptrick) = if j & 1 == 1 {
+            j -= len;
+            (1, 0)
+        } else {
+            (-1, 1)
+        };
+        while j != 0 {
+            j += jstep;
+            let t = childs[at(j)];
+            let p = endps[at(j - endptrick)] ^ endptrick as usize;
+            if t >= self.nvertex {
+                self.augment_blossom(t, self.endpoint[p]);
+            }
+            j += jstep;
+            let t = childs[at(j)];
+            if t >= self.nvertex {
+                self.augment_blossom(t, self.endpoint[p ^ 1]);
+            }
+            self.mate[self.endpoint[p]] = p ^ 1;
+            self.mate[self.endpoint[p ^ 1]] = p;
+        }
+        self.blossomchilds[b].rotate_left(i);
+        self.blossomendps[b].rotate_left(i);
+        self.blossombase[b] = self.blossombase[self.blossomchilds[b][0]];
+    }
+
+    fn augment_matching(&mut self, k: usize) {
+        let (v, w, _) = self.edges[k];
+        for (mut s, mut p) in [(v, 2 * k + 1), (w, 2 * k)] {
+            loop {
+                let bs = self.inblossom[s];
+                if bs >= self.nvertex {
+                    self.augment_blossom(bs, s);
+                }
+                self.mate[s] = p;
+                if self.labelend[bs] == NONE {
+                    // chegamos à raiz da árvore
+                    break;
+                }
+                let t = self.endpoint[self.labelend[bs]];
+                let bt = self.inblossom[t];
+                s = self.endpoint[self.labelend[bt]];
+                let j = self.endpoint[self.labelend[bt] ^ 1];
+                if bt >= self.nvertex {
+                    self.augment_blossom(bt, j);
+                }
+                self.mate[j] = self.labelend[bt];
+                p = self.labelend[bt] ^ 1;
+            }
+        }
+    }
+
+    fn run(mut self, maxcardinality: bool) -> Vec<Option<usize>> {
+        let nvertex = self.nvertex;
+        // Cada estágio aumenta o emparelhamento em uma aresta, no máximo `nvertex` estágios.
+        for _ in 0..nvertex {
+            self.label.fill(0);
+            self.bestedge.fill(NONE);
+            self.blossombestedges[nvertex..].fill(None);
+            self.allowedge.fill(false);
+            self.queue.clear();
+
+            for v in 0..nvertex {
+                if self.mate[v] == NONE && self.label[self.inblossom[v]] == 0 {
+                    self.assign_label(v, 1, NONE);
+                }
+            }
+
+            let mut augmented = false;
+            loop {
+                while !augmented {
+                    let Some(v) = self.queue.pop() else {
+                        break;
+                    };
+                    for p in self.neighbend[v].clone() {
+                        let k = p / 2;
+                        let w = self.endpoint[p];
+                        if self.inblossom[v] == self.inblossom[w] {
+                            // aresta interna de um blossom
+                            continue;
+                        }
+                        let mut kslack = 0;
+                        if !self.allowedge[k] {
+                            kslack = self.slack(k);
+                            if kslack <= 0 {
+                                self.allowedge[k] = true;
+                            }
+                        }
+                        if self.allowedge[k] {
+                            if self.label[self.inblossom[w]] == 0 {
+                                self.assign_label(w, 2, p ^ 1);
+                            } else if self.label[self.inblossom[w]] == 1 {
+                                let base = self.scan_blossom(v, w);
+                                if base != NONE {
+                                    self.add_blossom(base, k);
+                                } else {
+                                    self.augment_matching(k);
+                                    augmented = true;
+                                    break;
+                                }
+                            } else if self.label[w] == 0 {
+                                self.label[w] = 2;
+                                self.labelend[w] = p ^ 1;
+                            }
+                        } else if self.label[self.inblossom[w]] == 1 {
+                            let b = self.inblossom[v];
+                            if self.bestedge[b] == NONE || kslack < self.slack(self.bestedge[b]) {
+                                self.bestedge[b] = k;
+                            }
+                        } else if self.label[w] == 0
+                            && (self.bestedge[w] == NONE || kslack < self.slack(self.bestedge[w]))
+                        {
+                            self.bestedge[w] = k;
+                        }
+                    }
+                }
+                if augmented {
+                    break;
+                }
+
+                // Não há mais arestas justas: calcula o ajuste `delta` das variáveis duais.
+                let mut deltatype = 0;
+                let mut delta = 0;
+                let mut deltaedge = NONE;
+                let mut deltablossom = NONE;
+                if !maxcardinality {
+                    deltatype = 1;
+                    delta = *self.dualvar[..nvertex].iter().min().unwrap();
+                }
+                for v in 0..nvertex {
+                    if self.label[self.inblossom[v]] == 0 && self.bestedge[v] != NONE {
+                        let d = self.slack(self.bestedge[v]);
+                        if deltatype == 0 || d < delta {
+                            delta = d;
+                            deltatype = 2;
+                            deltaedge = self.bestedge[v];
+                        }
+                    }
+                }
+                for b in 0..2 * nvertex {
+                    if self.blossomparent[b] == NONE
+                        && self.label[b] == 1
+                        && self.bestedge[b] != NONE
+                    {
+                        let d = self.slack(self.bestedge[b]) / 2;
+                        if deltatype == 0 || d < delta {
+                            delta = d;
+                            deltatype = 3;
+                            deltaedge = self.bestedge[b];
+                        }
+                    }
+                }
+                for b in nvertex..2 * nvertex {
+                    if self.blossombase[b] != NONE
+                        && self.blossomparent[b] == NONE
+                        && self.label[b] == 2
+                        && (deltatype == 0 || self.dualvar[b] < delta)
+                    {
+                        delta = self.dualvar[b];
+                        deltatype = 4;
+                        deltablossom = b;
+                    }
+                }
+                if deltatype == 0 {
+                    // Nenhum ajuste possível: o emparelhamento já tem cardinalidade máxima.
+                    deltatype = 1;
+                    delta = (*self.dualvar[..nvertex].iter().min().unwrap()).max(0);
+                }
+
+                for v in 0..nvertex {
+                    match self.label[self.inblossom[v]] {
+                        1 => self.dualvar[v] -= delta,
+                        2 => self.dualvar[v] += delta,
+                        _ => (),
+                    }
+                }
+                for b in nvertex..2 * nvertex {
+                    if self.blossombase[b] != NONE && self.blossomparent[b] == NONE {
+                        match self.label[b] {
+                            1 => self.dualvar[b] += delta,
+                            2 => self.dualvar[b] -= delta,
+                            _ => (),
+                        }
+                    }
+                }
+
+                match deltatype {
+                    1 => break,
+                    2 => {
+                        self.allowedge[deltaedge] = true;
+                        let (mut i, j, _) = self.edges[deltaedge];
+                        if self.label[self.inblossom[i]] == 0 {
+                            i = j;
+                        }
+                        self.queue.push(i);
+                    }
+                    3 => {
+                        self.allowedge[deltaedge] = true;
+                        let (i, _, _) = self.edges[deltaedge];
+                        self.queue.push(i);
+                    }
+                    _ => self.expand_blossom(deltablossom, false),
+                }
+            }
+
+            if !augmented {
+                break;
+            }
+
+            // Expande S-blossoms cuja variável dual chegou a zero.
+            for b in nvertex..2 * nvertex {
+                if self.blossomparent[b] == NONE
+                    && self.blossombase[b] != NONE
+                    && self.label[b] == 1
+                    && self.dualvar[b] == 0
+                {
+                    self.expand_blossom(b, true);
+                }
+            }
+        }
+
+        self.mate
+            .iter()
+            .map(|&p| (p != NONE).then(|| self.endpoint[p]))
+            .collect()
+    }
+}
+
+/// Calcula um emparelhamento de peso máximo no grafo com `nvertex` vértices e arestas `edges`
+/// no formato `(i, j, peso)`.
+///
+/// Se `maxcardinality` for verdadeiro, retorna o emparelhamento de peso máximo dentre os de
+/// cardinalidade máxima. O resultado associa cada vértice ao seu par, caso exista.
+pub fn max_weight_matching(
+    nvertex: usize,
+    edges: &[(usize, usize, i64)],
+    maxcardinality: bool,
+) -> Vec<Option<usize>> {
+    if edges.is_empty() {
+        return vec![None; nvertex];
+    }
+    Matcher::new(nvertex, edges).run(maxcardinality)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// Melhor (cardinalidade, peso) dentre todos os emparelhamentos, por enumeração. Com
+    /// `maxcardinality` falso, a cardinalidade é ignorada.
+    fn brute_force(
+        edges: &[(usize, usize, i64)],
+        used: &mut Vec<bool>,
+        from: usize,
+        maxcardinality: bool,
+    ) -> (usize, i64) {
+        let mut best = (0, 0);
+        for k in from..edges.len() {
+            let (i, j, w) = edges[k];
+            if used[i] || used[j] {
+                continue;
+            }
+            used[i] = true;
+            used[j] = true;
+            let (count, weight) = brute_force(edges, used, k + 1, maxcardinality);
+            used[i] = false;
+            used[j] = false;
+            let candidate = (if maxcardinality { count + 1 } else { 0 }, weight + w);
+            best = best.max(candidate);
+        }
+        best
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rand = StdRng::seed_from_u64(7);
+        for _ in 0..500 {
+            let nvertex = rand.gen_range(1..=8);
+            let mut edges = Vec::new();
+            for i in 0..nvertex {
+                for j in i + 1..nvertex {
+                    if rand.gen_bool(0.6) {
+                        edges.push((i, j, rand.gen_range(-5..20)));
+                    }
+                }
+            }
+            for maxcardinality in [false, true] {
+                let mate = max_weight_matching(nvertex, &edges, maxcardinality);
+
+                // o resultado deve ser um emparelhamento válido sobre as arestas dadas
+                let mut count = 0;
+                let mut weight = 0;
+                for (i, m) in mate.iter().enumerate() {
+                    let Some(j) = *m else { continue };
+                    assert_eq!(mate[j], Some(i), "emparelhamento assimétrico: {mate:?}");
+                    if i < j {
+                        let &(_, _, w) = edges
+                            .iter()
+                            .find(|(a, b, _)| (*a, *b) == (i, j))
+                            .expect("par sem aresta");
+                        count += 1;
+                        weight += w;
+                    }
+                }
+                let expected = brute_force(&edges, &mut vec![false; nvertex], 0, maxcardinality);
+                let found = (if maxcardinality { count } else { 0 }, weight);
+                assert_eq!(found, expected, "{nvertex} vértices, arestas {edges:?}");
+            }
+        }
+    }
+}
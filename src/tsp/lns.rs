@@ -0,0 +1,135 @@
+//! Operadores de destruição e reparação de rotas para a busca em vizinhança grande. A destruição
+//! remove nós da rota, devolvendo a rota parcial e os nós removidos; a reparação os reinsere.
+use rand::{seq::SliceRandom, Rng};
+
+use super::Solution;
+use crate::{Graph, Node, Weight};
+
+/// Rota parcial e nós removidos dela.
+pub type Destroyed = (Vec<Node>, Vec<Node>);
+
+/// Remove `q` nós aleatórios.
+pub fn random_removal(s: &Solution, q: usize, rand: &mut impl Rng) -> Destroyed {
+    let mut partial = s.nodes.to_vec();
+    let mut removed = Vec::with_capacity(q);
+    for _ in 0..q.min(partial.len()) {
+        let i = rand.gen_range(0..partial.len());
+        removed.push(partial.remove(i));
+    }
+    (partial, removed)
+}
+
+/// Economia ao remover o nó da posição `i` da rota parcial.
+fn removal_gain(g: &dyn Graph, tour: &[Node], i: usize) -> Weight {
+    let n = tour.len();
+    let (prev, node, next) = (tour[(i + n - 1) % n], tour[i], tour[(i + 1) % n]);
+    let w = |a, b| g.get_edge_weight(a, b).unwrap();
+    w(prev, node) + w(node, next) - w(prev, next)
+}
+
+/// Posição sorteada em uma lista ordenada de `len > 0` elementos: `y^p · len`, com `y` uniforme
+/// em [0, 1). Quanto maior `p`, mais próxima do início.
+fn randomized_position(len: usize, p: f64, rand: &mut impl Rng) -> usize {
+    // `y^p` pode arredondar para 1
+    ((rand.gen::<f64>().powf(p) * len as f64) as usize).min(len - 1)
+}
+
+/// Remove, um a um, nós de maior economia de remoção. A escolha é aleatorizada: com os nós
+/// ordenados por economia, escolhe a posição `y^p · n`, com `y` uniforme em [0, 1) e `p > 0`.
+pub fn worst_removal(s: &Solution, q: usize, p: f64, rand: &mut impl Rng) -> Destroyed {
+    let g = s.graph();
+    let mut partial = s.nodes.to_vec();
+    let mut removed = Vec::with_capacity(q);
+    for _ in 0..q.min(partial.len().saturating_sub(2)) {
+        let mut positions = (0..partial.len()).collect::<Vec<_>>();
+        positions.sort_by_cached_key(|i| std::cmp::Reverse(removal_gain(g, &partial, *i)));
+        let chosen = randomized_position(positions.len(), p, rand);
+        removed.push(partial.remove(positions[chosen]));
+    }
+    (partial, removed)
+}
+
+/// Remoção relacionada (de Shaw): começa por um nó aleatório e remove, a cada passo, o nó da
+/// rota mais próximo de um dos já removidos, escolhido ao acaso. A escolha é aleatorizada como
+/// em [`worst_removal`].
+pub fn shaw_removal(s: &Solution, q: usize, p: f64, rand: &mut impl Rng) -> Destroyed {
+    let g = s.graph();
+    let mut partial = s.nodes.to_vec();
+    let mut removed = Vec::with_capacity(q);
+    if q == 0 || partial.is_empty() {
+        return (partial, removed);
+    }
+    let seed = rand.gen_range(0..partial.len());
+    removed.push(partial.remove(seed));
+    while removed.len() < q && !partial.is_empty() {
+        let reference = *removed.choose(rand).unwrap();
+        let mut positions = (0..partial.len()).collect::<Vec<_>>();
+        positions.sort_by_cached_key(|i| g.get_edge_weight(reference, partial[*i]).unwrap());
+        let chosen = randomized_position(positions.len(), p, rand);
+        removed.push(partial.remove(positions[chosen]));
+    }
+    (partial, removed)
+}
+
+/// Menor custo de inserir `node` na rota parcial, e a posição em que deve ser inserido.
+fn cheapest_insertion(g: &dyn Graph, tour: &[Node], node: Node) -> (Weight, usize) {
+    let n = tour.len();
+    if n < 2 {
+        return (0.0.into(), n);
+    }
+    let w = |a, b| g.get_edge_weight(a, b).unwrap();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (tour[i], tour[(i + 1) % n]);
+            (w(a, node) + w(node, b) - w(a, b), i + 1)
+        })
+        .min()
+        .unwrap()
+}
+
+/// Insere os nós removidos, em ordem aleatória, cada um na posição mais barata.
+pub fn greedy_insertion<'g>(
+    g: &'g dyn Graph,
+    (mut tour, mut removed): Destroyed,
+    rand: &mut impl Rng,
+) -> Solution<'g> {
+    removed.shuffle(rand);
+    for node in removed {
+        let (_, position) = cheapest_insertion(g, &tour, node);
+        tour.insert(position, node);
+    }
+    Solution::new(tour, g)
+}
+
+/// Inserção por arrependimento: insere primeiro o nó com a maior diferença entre o custo de
+/// inseri-lo na segunda melhor posição e na melhor, já que adiá-lo custaria mais.
+pub fn regret_insertion<'g>(g: &'g dyn Graph, (mut tour, mut removed): Destroyed) -> Solution<'g> {
+    let w = |a, b| g.get_edge_weight(a, b).unwrap();
+    while !removed.is_empty() {
+        let n = tour.len();
+        let (index, position) = if n < 2 {
+            (0, n)
+        } else {
+            let (_, index, position) = removed
+                .iter()
+                .enumerate()
+                .map(|(k, &node)| {
+                    let mut costs = (0..n)
+                        .map(|i| {
+                            let (a, b) = (tour[i], tour[(i + 1) % n]);
+                            (w(a, node) + w(node, b) - w(a, b), i + 1)
+                        })
+                        .collect::<Vec<_>>();
+                    costs.select_nth_unstable(1);
+                    costs[..2].sort_unstable();
+                    let regret = costs[1].0 - costs[0].0;
+                    (regret, k, costs[0].1)
+                })
+                .max()
+                .unwrap();
+            (index, position)
+        };
+        tour.insert(position, removed.swap_remove(index));
+    }
+    Solution::new(tour, g)
+}
@@ -3,48 +3,417 @@ use std::{
     ops::Sub,
 };
 
-use crate::{Graph, Weight};
+use crate::{GraphMat, GraphMut, Weight};
 
 pub fn euclidean_distance(a: [Weight; 2], b: [Weight; 2]) -> Weight {
     let xd = a[0].sub(b[0]).abs();
     let yd = a[1].sub(b[1]).abs();
-    let d = (xd.powi(2) + yd.powi(2)) as f64;
+    let d = xd.powi(2) + yd.powi(2);
     let d = d.sqrt() + 0.5;
     d.into()
 }
 
-pub fn fill_tsp_graph(file: &mut dyn BufRead, graph: &mut dyn Graph) -> io::Result<()> {
+/// Lê um arquivo de números separados por espaço, uma linha por vez, no formato esperado por
+/// [`crate::fill_graph`].
+pub fn read_graph_input(file: &mut dyn BufRead) -> io::Result<Vec<Vec<u32>>> {
+    file.lines()
+        .map(|line| {
+            Ok(line?
+                .split_whitespace()
+                .map(|num| num.parse().expect("Número inválido"))
+                .collect())
+        })
+        .filter(|line| !matches!(line, Ok(nums) if Vec::is_empty(nums)))
+        .collect()
+}
+
+/// Distância `CEIL_2D` da TSPLIB: distância euclidiana arredondada para cima.
+pub fn ceil_distance(a: [Weight; 2], b: [Weight; 2]) -> Weight {
+    let xd = a[0] - b[0];
+    let yd = a[1] - b[1];
+    (xd.powi(2) + yd.powi(2)).sqrt().ceil().into()
+}
+
+/// Distância pseudo-euclidiana `ATT` da TSPLIB.
+pub fn att_distance(a: [Weight; 2], b: [Weight; 2]) -> Weight {
+    let xd = a[0] - b[0];
+    let yd = a[1] - b[1];
+    let r = ((xd.powi(2) + yd.powi(2)) / 10.0).sqrt();
+    let t = r.round();
+    if t < r {
+        (t + 1.0).into()
+    } else {
+        t.into()
+    }
+}
+
+/// Distância geográfica `GEO` da TSPLIB, com coordenadas no formato `GRAUS.MINUTOS`.
+pub fn geo_distance(a: [Weight; 2], b: [Weight; 2]) -> Weight {
+    // A TSPLIB define a distância com este valor truncado de π.
+    #[allow(clippy::approx_constant)]
+    const PI: f64 = 3.141592;
+    const RRR: f64 = 6378.388;
+    let radians = |x: Weight| {
+        let deg = x.trunc();
+        let min = *x - deg;
+        PI * (deg + 5.0 * min / 3.0) / 180.0
+    };
+    let (lat_a, lon_a) = (radians(a[0]), radians(a[1]));
+    let (lat_b, lon_b) = (radians(b[0]), radians(b[1]));
+    let q1 = (lon_a - lon_b).cos();
+    let q2 = (lat_a - lat_b).cos();
+    let q3 = (lat_a + lat_b).cos();
+    (RRR * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0)
+        .trunc()
+        .into()
+}
+
+/// Funções de distância entre coordenadas suportadas, nomeadas segundo o campo
+/// `EDGE_WEIGHT_TYPE` da TSPLIB.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Metric {
+    /// `EUC_2D`, calculada por [`euclidean_distance`].
+    #[default]
+    Euclidean,
+    /// `CEIL_2D`
+    Ceil,
+    /// `ATT`
+    Att,
+    /// `GEO`
+    Geo,
+}
+
+impl Metric {
+    pub fn from_tsplib(name: &str) -> Option<Self> {
+        match name {
+            "EUC_2D" => Some(Self::Euclidean),
+            "CEIL_2D" => Some(Self::Ceil),
+            "ATT" => Some(Self::Att),
+            "GEO" => Some(Self::Geo),
+            _ => None,
+        }
+    }
+    pub fn distance(self, a: [Weight; 2], b: [Weight; 2]) -> Weight {
+        match self {
+            Self::Euclidean => euclidean_distance(a, b),
+            Self::Ceil => ceil_distance(a, b),
+            Self::Att => att_distance(a, b),
+            Self::Geo => geo_distance(a, b),
+        }
+    }
+    /// Indica se a distância entre dois pontos nunca é menor que a distância entre suas
+    /// projeções num mesmo eixo, o que permite podar buscas por vizinhos mais próximos.
+    pub fn is_planar(self) -> bool {
+        self != Self::Geo
+    }
+}
+
+/// Formatos de matriz do campo `EDGE_WEIGHT_FORMAT` da TSPLIB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixFormat {
+    FullMatrix,
+    UpperRow,
+    LowerRow,
+    UpperDiagRow,
+    LowerDiagRow,
+}
+
+impl MatrixFormat {
+    pub fn from_tsplib(name: &str) -> Option<Self> {
+        match name {
+            "FULL_MATRIX" => Some(Self::FullMatrix),
+            "UPPER_ROW" => Some(Self::UpperRow),
+            "LOWER_ROW" => Some(Self::LowerRow),
+            "UPPER_DIAG_ROW" => Some(Self::UpperDiagRow),
+            "LOWER_DIAG_ROW" => Some(Self::LowerDiagRow),
+            _ => None,
+        }
+    }
+
+    /// Posições `(linha, coluna)` da matriz na ordem em que aparecem no arquivo.
+    fn positions(self, n: usize) -> Box<dyn Iterator<Item = (usize, usize)>> {
+        let rows = 0..n;
+        match self {
+            Self::FullMatrix => Box::new(rows.flat_map(move |i| (0..n).map(move |j| (i, j)))),
+            Self::UpperRow => Box::new(rows.flat_map(move |i| (i + 1..n).map(move |j| (i, j)))),
+            Self::LowerRow => Box::new(rows.flat_map(|i| (0..i).map(move |j| (i, j)))),
+            Self::UpperDiagRow => Box::new(rows.flat_map(move |i| (i..n).map(move |j| (i, j)))),
+            Self::LowerDiagRow => Box::new(rows.flat_map(|i| (0..=i).map(move |j| (i, j)))),
+        }
+    }
+}
+
+/// Como os pesos das arestas são dados em um arquivo TSPLIB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeWeights {
+    /// Calculados a partir das coordenadas da `NODE_COORD_SECTION`.
+    Coords(Metric),
+    /// Dados explicitamente na `EDGE_WEIGHT_SECTION`.
+    Explicit(MatrixFormat),
+}
+
+/// Campos relevantes do cabeçalho de um arquivo TSPLIB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TspHeader {
+    /// `TYPE: ATSP`, ou seja, o peso de `(a, b)` pode diferir do peso de `(b, a)`.
+    pub asymmetric: bool,
+    pub dimension: usize,
+    pub weights: EdgeWeights,
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Lê o cabeçalho de um arquivo TSPLIB, parando no início da seção de dados correspondente a
+/// `weights`.
+pub fn read_tsp_header(file: &mut dyn BufRead) -> io::Result<TspHeader> {
+    let mut asymmetric = false;
+    let mut dimension = 0;
+    let mut metric = Some(Metric::default());
+    let mut format = None;
     let mut buf = String::new();
     loop {
         buf.clear();
-        file.read_line(&mut buf)?;
+        if file.read_line(&mut buf)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Esperava seção NODE_COORD_SECTION ou EDGE_WEIGHT_SECTION",
+            ));
+        }
         let buf = buf.trim();
-        if buf == "NODE_COORD_SECTION" {
+        if buf == "NODE_COORD_SECTION" || buf == "EDGE_WEIGHT_SECTION" {
             break;
         }
+        let Some((key, value)) = buf.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "TYPE" => asymmetric = value == "ATSP",
+            "DIMENSION" => dimension = value.parse().map_err(|_| invalid_data("DIMENSION"))?,
+            "EDGE_WEIGHT_TYPE" if value == "EXPLICIT" => metric = None,
+            "EDGE_WEIGHT_TYPE" => {
+                metric = Some(
+                    Metric::from_tsplib(value)
+                        .ok_or_else(|| invalid_data("EDGE_WEIGHT_TYPE não suportado"))?,
+                )
+            }
+            "EDGE_WEIGHT_FORMAT" => {
+                format = Some(
+                    MatrixFormat::from_tsplib(value)
+                        .ok_or_else(|| invalid_data("EDGE_WEIGHT_FORMAT não suportado"))?,
+                )
+            }
+            _ => (),
+        }
     }
+    let weights = match metric {
+        Some(metric) => EdgeWeights::Coords(metric),
+        // A TSPLIB só usa matrizes completas para instâncias assimétricas.
+        None => EdgeWeights::Explicit(format.unwrap_or(MatrixFormat::FullMatrix)),
+    };
+    Ok(TspHeader {
+        asymmetric,
+        dimension,
+        weights,
+    })
+}
+
+/// Lê a seção `NODE_COORD_SECTION` de um arquivo TSPLIB, retornando a métrica declarada em
+/// `EDGE_WEIGHT_TYPE` e as coordenadas de cada cidade.
+pub fn read_tsp_coords(file: &mut dyn BufRead) -> io::Result<(Metric, Vec<[Weight; 2]>)> {
+    let EdgeWeights::Coords(metric) = read_tsp_header(file)?.weights else {
+        return Err(invalid_data("Esperava instância com coordenadas"));
+    };
+    Ok((metric, read_coords_section(file)?))
+}
+
+fn read_coords_section(file: &mut dyn BufRead) -> io::Result<Vec<[Weight; 2]>> {
+    let mut buf = String::new();
     let mut locations = vec![];
     loop {
         buf.clear();
-        file.read_line(&mut buf)?;
+        let read = file.read_line(&mut buf)?;
         let buf = buf.trim();
-        if buf == "EOF" {
+        if read == 0 || buf == "EOF" {
             break;
         }
+        if buf.is_empty() {
+            continue;
+        }
         let nums = buf
-            .split(" ")
-            .map(|i| i.trim().parse::<Weight>().expect("Número invalido"))
+            .split_whitespace()
+            .map(|i| i.parse::<Weight>().expect("Número invalido"))
             .collect::<Vec<Weight>>();
 
         let [_id, x, y] = nums[..3] else {
             panic!("Expected at least 3 elements per line")
         };
-        locations.push((graph.add_node(), [x, y]));
+        locations.push([x, y]);
+    }
+    Ok(locations)
+}
+
+/// Lê os números da `EDGE_WEIGHT_SECTION`, que terminam no fim do arquivo ou na próxima seção.
+fn read_weight_section(file: &mut dyn BufRead) -> io::Result<Vec<Weight>> {
+    let mut weights = vec![];
+    for line in file.lines() {
+        let line = line?;
+        if line
+            .trim_start()
+            .starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            break;
+        }
+        weights.extend(
+            line.split_whitespace()
+                .map(|i| i.parse::<Weight>().expect("Número invalido")),
+        );
     }
-    for (a, a_loc) in &locations {
-        for (b, b_loc) in &locations {
-            graph.add_edge(*a, *b, euclidean_distance(*a_loc, *b_loc))
+    Ok(weights)
+}
+
+/// Preenche `graph` com uma instância TSPLIB, seja por coordenadas ou por matriz explícita.
+///
+/// Laços (arestas de um nó para ele mesmo) nunca são adicionados, já que a diagonal das matrizes
+/// explícitas costuma conter valores arbitrários. Instâncias assimétricas (`TYPE: ATSP`) exigem
+/// um grafo dirigido; instâncias simétricas lidas em um grafo dirigido recebem os dois arcos de
+/// cada par.
+pub fn fill_tsp_graph(file: &mut dyn BufRead, graph: &mut dyn GraphMut) -> io::Result<()> {
+    let header = read_tsp_header(file)?;
+    if header.asymmetric && !graph.is_directed() {
+        return Err(invalid_data(
+            "Instâncias assimétricas exigem um grafo dirigido",
+        ));
+    }
+    match header.weights {
+        EdgeWeights::Coords(metric) => {
+            let locations = read_coords_section(file)?
+                .into_iter()
+                .map(|loc| (graph.add_node(), loc))
+                .collect::<Vec<_>>();
+            for (a, a_loc) in &locations {
+                for (b, b_loc) in locations.iter().filter(|(b, _)| b != a) {
+                    graph.add_edge(*a, *b, metric.distance(*a_loc, *b_loc))
+                }
+            }
+        }
+        EdgeWeights::Explicit(format) => {
+            let n = header.dimension;
+            let nodes = graph.add_nodes(n);
+            let weights = read_weight_section(file)?;
+            let positions = format.positions(n).collect::<Vec<_>>();
+            if weights.len() < positions.len() {
+                return Err(invalid_data("EDGE_WEIGHT_SECTION incompleta"));
+            }
+            // Em um grafo dirigido, uma matriz triangular de uma instância simétrica precisa dos
+            // dois arcos de cada par.
+            let mirror = !header.asymmetric && graph.is_directed();
+            for ((i, j), w) in positions.into_iter().zip(weights) {
+                if i != j {
+                    graph.add_edge(nodes[i], nodes[j], w);
+                    if mirror {
+                        graph.add_edge(nodes[j], nodes[i], w);
+                    }
+                }
+            }
         }
     }
     Ok(())
 }
+
+/// Lê uma instância TSPLIB em uma matriz de adjacência, dirigida caso a instância seja
+/// assimétrica.
+pub fn read_tsp_graph(file: &mut dyn BufRead) -> io::Result<GraphMat> {
+    // O cabeçalho precisa ser lido antes de escolher o grafo, então guardamos o arquivo inteiro.
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents)?;
+    let header = read_tsp_header(&mut &contents[..])?;
+    let mut graph = if header.asymmetric {
+        GraphMat::directed()
+    } else {
+        GraphMat::default()
+    };
+    fill_tsp_graph(&mut &contents[..], &mut graph)?;
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Graph;
+
+    #[test]
+    fn reads_tsp_coords() {
+        let input = "NAME: teste\nTYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EUC_2D\n\
+            NODE_COORD_SECTION\n1 0 0\n2 3 4\n3 6 8\nEOF\n";
+        let graph = read_tsp_graph(&mut input.as_bytes()).unwrap();
+        assert!(!graph.is_directed());
+        assert_eq!(graph.node_count(), 3);
+        let expected =
+            Metric::Euclidean.distance([0.0.into(), 0.0.into()], [3.0.into(), 4.0.into()]);
+        assert_eq!(graph.get_edge_weight(0, 1), Some(expected));
+        assert_eq!(graph.get_edge_weight(1, 0), Some(expected));
+        assert_eq!(graph.get_edge_weight(1, 1), None);
+    }
+
+    #[test]
+    fn mirrors_symmetric_matrices_in_directed_graphs() {
+        let input = "TYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\n\
+            EDGE_WEIGHT_FORMAT: LOWER_DIAG_ROW\nEDGE_WEIGHT_SECTION\n0\n1 0\n2 3 0\nEOF\n";
+        let mut graph = GraphMat::directed();
+        fill_tsp_graph(&mut input.as_bytes(), &mut graph).unwrap();
+        for (a, b, w) in [(0, 1, 1.0), (0, 2, 2.0), (1, 2, 3.0)] {
+            assert_eq!(graph.get_edge_weight(a, b), Some(w.into()));
+            assert_eq!(graph.get_edge_weight(b, a), Some(w.into()));
+        }
+        assert_eq!(graph.get_edge_weight(0, 0), None);
+    }
+
+    #[test]
+    fn reads_explicit_matrices() {
+        let upper = "TYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\n\
+            EDGE_WEIGHT_FORMAT: UPPER_ROW\nEDGE_WEIGHT_SECTION\n1 2\n3\nEOF\n";
+        let full = "TYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\n\
+            EDGE_WEIGHT_FORMAT: FULL_MATRIX\nEDGE_WEIGHT_SECTION\n0 1 2\n1 0 3\n2 3 0\nEOF\n";
+        for input in [upper, full] {
+            let graph = read_tsp_graph(&mut input.as_bytes()).unwrap();
+            assert!(!graph.is_directed());
+            for (a, b, w) in [(0, 1, 1.0), (0, 2, 2.0), (1, 2, 3.0)] {
+                assert_eq!(graph.get_edge_weight(a, b), Some(w.into()), "{input}");
+                assert_eq!(graph.get_edge_weight(b, a), Some(w.into()), "{input}");
+            }
+            // a diagonal é ignorada
+            assert_eq!(graph.get_edge_weight(1, 1), None);
+        }
+    }
+
+    #[test]
+    fn reads_atsp_as_directed() {
+        let input = "TYPE: ATSP\nDIMENSION: 2\nEDGE_WEIGHT_TYPE: EXPLICIT\n\
+            EDGE_WEIGHT_FORMAT: FULL_MATRIX\nEDGE_WEIGHT_SECTION\n9999 4\n7 9999\nEOF\n";
+        let graph = read_tsp_graph(&mut input.as_bytes()).unwrap();
+        assert!(graph.is_directed());
+        assert_eq!(graph.get_edge_weight(0, 1), Some(4.0.into()));
+        assert_eq!(graph.get_edge_weight(1, 0), Some(7.0.into()));
+    }
+
+    #[test]
+    fn rejects_unsupported_tsp_header() {
+        let input = "TYPE: TSP\nEDGE_WEIGHT_TYPE: XRAY1\nNODE_COORD_SECTION\n";
+        let err = read_tsp_header(&mut input.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err = read_tsp_header(&mut "TYPE: TSP\n".as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn rejects_incomplete_weight_section() {
+        let input = "TYPE: TSP\nDIMENSION: 3\nEDGE_WEIGHT_TYPE: EXPLICIT\n\
+            EDGE_WEIGHT_FORMAT: UPPER_ROW\nEDGE_WEIGHT_SECTION\n1 2\nEOF\n";
+        let err = read_tsp_graph(&mut input.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,197 @@
+//! Busca em vizinhança variável (VNS), compartilhada entre os problemas: perturba a solução
+//! corrente em vizinhanças cada vez maiores, desce até um ótimo local e volta à primeira
+//! vizinhança sempre que a solução é aceita.
+use rand::Rng;
+
+use crate::knapsack::{self, repair, KnapsackSolution};
+use crate::tsp;
+
+/// Soluções sobre as quais a VNS pode ser aplicada.
+pub trait Neighbourhoods: Clone {
+    type Neighbourhood: Copy + 'static;
+
+    /// Vizinhanças da descida e da perturbação, em ordem.
+    const NEIGHBOURHOODS: &'static [Self::Neighbourhood];
+
+    /// Custo da solução, a ser minimizado.
+    fn cost(&self) -> f64;
+    /// Se a solução pode ser devolvida como resultado.
+    fn is_feasible(&self) -> bool;
+    /// Solução viável obtida a partir desta, que serve como a melhor inicial.
+    fn to_feasible(&self) -> Self;
+    /// Aplica o primeiro movimento da vizinhança que melhora a solução. Devolve se encontrou
+    /// algum.
+    fn improve(&mut self, neighbourhood: Self::Neighbourhood) -> bool;
+    /// Aplica um movimento aleatório da vizinhança.
+    fn perturb(&mut self, neighbourhood: Self::Neighbourhood, rand: &mut impl Rng);
+    /// Distância entre as soluções, usada pela variante enviesada.
+    fn distance(&self, other: &Self) -> usize;
+}
+
+impl Neighbourhoods for tsp::Solution<'_> {
+    type Neighbourhood = tsp::Neighbourhood;
+
+    const NEIGHBOURHOODS: &'static [tsp::Neighbourhood] = &tsp::Neighbourhood::ALL;
+
+    fn cost(&self) -> f64 {
+        self.value.0
+    }
+    fn is_feasible(&self) -> bool {
+        true
+    }
+    fn to_feasible(&self) -> Self {
+        self.clone()
+    }
+    fn improve(&mut self, neighbourhood: tsp::Neighbourhood) -> bool {
+        tsp::Solution::improve(self, neighbourhood)
+    }
+    fn perturb(&mut self, neighbourhood: tsp::Neighbourhood, rand: &mut impl Rng) {
+        *self = self.random_move(neighbourhood, rand);
+    }
+    /// Quantidade de arestas de `self` que não estão em `other`.
+    fn distance(&self, other: &Self) -> usize {
+        let edges = |s: &Self| {
+            let n = s.nodes.len();
+            (0..n)
+                .map(|i| {
+                    let (x, y) = (s.nodes[i], s.nodes[(i + 1) % n]);
+                    if s.graph().is_directed() {
+                        (x, y)
+                    } else {
+                        (x.min(y), x.max(y))
+                    }
+                })
+                .collect::<std::collections::HashSet<_>>()
+        };
+        edges(self).difference(&edges(other)).count()
+    }
+}
+
+/// Como a mochila é um problema de max, o custo é o valor com o sinal trocado.
+impl<S: KnapsackSolution> Neighbourhoods for S {
+    type Neighbourhood = knapsack::Neighbourhood;
+
+    const NEIGHBOURHOODS: &'static [knapsack::Neighbourhood] = &knapsack::Neighbourhood::VND;
+
+    fn cost(&self) -> f64 {
+        -self.value().0
+    }
+    fn is_feasible(&self) -> bool {
+        KnapsackSolution::is_feasible(self)
+    }
+    fn to_feasible(&self) -> Self {
+        repair::decode(self)
+    }
+    fn improve(&mut self, neighbourhood: knapsack::Neighbourhood) -> bool {
+        KnapsackSolution::improve(self, neighbourhood)
+    }
+    fn perturb(&mut self, neighbourhood: knapsack::Neighbourhood, rand: &mut impl Rng) {
+        let mv = self.random_move(neighbourhood, rand);
+        self.apply(&mv);
+    }
+    /// Distância de Hamming entre as soluções.
+    fn distance(&self, other: &Self) -> usize {
+        (self.items().clone() ^ other.items()).count_ones()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Variant {
+    /// Após a perturbação, desce apenas na vizinhança `descent`.
+    Basic,
+    /// Após a perturbação, aplica a descida em vizinhança variável (VND).
+    General,
+    /// Como a geral, mas aceita soluções um pouco piores se estiverem distantes da corrente.
+    Skewed,
+}
+
+impl std::str::FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "basic" => Ok(Self::Basic),
+            "general" => Ok(Self::General),
+            "skewed" => Ok(Self::Skewed),
+            _ => Err(format!("Variante desconhecida: {s}")),
+        }
+    }
+}
+
+/// Descida em vizinhança variável: volta à primeira vizinhança sempre que alguma melhora a
+/// solução, e termina quando nenhuma melhora.
+pub fn vnd<S: Neighbourhoods>(s: &mut S) {
+    let mut k = 0;
+    while k < S::NEIGHBOURHOODS.len() {
+        if s.improve(S::NEIGHBOURHOODS[k]) {
+            k = 0;
+        } else {
+            k += 1;
+        }
+    }
+}
+
+/// Aplica `k` movimentos aleatórios da `k`-ésima vizinhança, com `k` de 1 até a quantidade de
+/// vizinhanças.
+pub fn shake<S: Neighbourhoods>(s: &S, k: usize, rand: &mut impl Rng) -> S {
+    let neighbourhood = S::NEIGHBOURHOODS[k - 1];
+    let mut s = s.clone();
+    for _ in 0..k {
+        s.perturb(neighbourhood, rand);
+    }
+    s
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Vns<N> {
+    /// Quantidade de perturbações.
+    pub i_max: usize,
+    pub variant: Variant,
+    pub descent: N,
+    /// Custo tolerado por unidade de distância na variante enviesada.
+    pub skew: f64,
+}
+
+impl<N: Copy> Vns<N> {
+    /// Desce a partir de `s0` e aplica a VNS, devolvendo a melhor solução viável encontrada. A
+    /// perturbação percorre as vizinhanças de [`Neighbourhoods::NEIGHBOURHOODS`], recomeçando da
+    /// primeira após a última.
+    pub fn run<S>(&self, s0: &S, rand: &mut impl Rng) -> S
+    where
+        S: Neighbourhoods<Neighbourhood = N>,
+    {
+        let k_max = S::NEIGHBOURHOODS.len();
+
+        let mut s = s0.clone();
+        vnd(&mut s);
+        // a melhor solução deve ser viável, mesmo que a corrente não seja
+        let mut s_best = s.to_feasible();
+        let mut k = 1;
+        for _ in 0..self.i_max {
+            let mut s_prime = shake(&s, k, rand);
+            match self.variant {
+                Variant::Basic => while s_prime.improve(self.descent) {},
+                Variant::General | Variant::Skewed => vnd(&mut s_prime),
+            }
+
+            if s_prime.cost() < s_best.cost() && s_prime.is_feasible() {
+                s_best = s_prime.clone();
+            }
+
+            let accept = match self.variant {
+                Variant::Basic | Variant::General => s_prime.cost() < s.cost(),
+                Variant::Skewed => {
+                    s_prime.cost() - self.skew * (s.distance(&s_prime) as f64) < s.cost()
+                }
+            };
+            if accept {
+                s = s_prime;
+                k = 1;
+            } else {
+                // próxima vizinhança, recomeçando da primeira após a última
+                k = k % k_max + 1;
+            }
+        }
+        s_best
+    }
+}